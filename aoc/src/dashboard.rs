@@ -0,0 +1,372 @@
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Sparkline, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use util::answers::Registry;
+use util::history::History;
+
+use crate::watch::strip_colours;
+use crate::workspace::Workspace;
+
+const SPARK: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const SPARK_RUNS: usize = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Status {
+    /// The solution still contains `todo!()`.
+    Stub,
+    NotRun,
+    Solved,
+    /// The last answers match the right answers in the registry.
+    Verified,
+    /// A last answer differs from a right answer in the registry.
+    Mismatch,
+}
+
+impl Status {
+    fn cell(self) -> Cell<'static> {
+        let (text, colour) = match self {
+            Status::Stub => ("stub", Color::DarkGray),
+            Status::NotRun => ("not run", Color::Gray),
+            Status::Solved => ("solved", Color::Yellow),
+            Status::Verified => ("verified", Color::Green),
+            Status::Mismatch => ("mismatch", Color::Red),
+        };
+
+        Cell::from(text).style(Style::default().fg(colour))
+    }
+}
+
+struct DayInfo {
+    year: u16,
+    day: u8,
+    stub: bool,
+    history: History,
+    registry: Registry,
+}
+
+impl DayInfo {
+    fn load(workspace: &Workspace, year: u16, day: u8) -> Result<Self, String> {
+        let source = workspace.path(format!("{}/src/main.rs", Workspace::day_dir(year, day)));
+        let stub = std::fs::read_to_string(source)
+            .map(|s| s.contains("todo!()"))
+            .unwrap_or(true);
+
+        let history =
+            History::load(&workspace.path("history"), year, day).map_err(|e| e.to_string())?;
+        let registry =
+            Registry::load(&workspace.path("answers"), year, day).map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            year,
+            day,
+            stub,
+            history,
+            registry,
+        })
+    }
+
+    fn status(&self) -> Status {
+        if self.stub {
+            return Status::Stub;
+        }
+
+        let Some(last) = self.history.last() else {
+            return Status::NotRun;
+        };
+
+        let checks: Vec<bool> = [(1, &last.part1.0), (2, &last.part2.0)]
+            .into_iter()
+            .filter_map(|(part, answer)| Some(self.registry.right_answer(part)? == answer))
+            .collect();
+
+        if checks.contains(&false) {
+            Status::Mismatch
+        } else if checks.is_empty() {
+            Status::Solved
+        } else {
+            Status::Verified
+        }
+    }
+
+    fn timings(&self) -> Vec<u64> {
+        self.history
+            .runs
+            .iter()
+            .map(|r| r.total().as_micros() as u64)
+            .collect()
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    format!("{:.1} ms", d.as_secs_f64() * 1000.0)
+}
+
+/// A one line summary of an answer, which can span several lines for days
+/// that draw their answer.
+fn short_answer(answer: &str) -> String {
+    let mut lines = answer.lines().filter(|l| !l.trim().is_empty());
+    match (lines.next(), lines.next()) {
+        (Some(first), None) => first.to_owned(),
+        (Some(first), Some(_)) => format!("{first}…"),
+        (None, _) => String::new(),
+    }
+}
+
+fn text_sparkline(values: &[u64]) -> String {
+    let values = &values[values.len().saturating_sub(SPARK_RUNS)..];
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+
+    values
+        .iter()
+        .map(|&v| SPARK[(v * (SPARK.len() as u64 - 1) / max) as usize])
+        .collect()
+}
+
+struct Dashboard {
+    workspace: Workspace,
+    days: Vec<DayInfo>,
+    table: TableState,
+    /// Title and text of the output of the last run, when it is being shown.
+    output: Option<(String, String)>,
+    scroll: u16,
+    message: String,
+}
+
+impl Dashboard {
+    fn new(workspace: Workspace) -> Result<Self, String> {
+        let days = workspace
+            .days()?
+            .into_iter()
+            .map(|(year, day)| DayInfo::load(&workspace, year, day))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            workspace,
+            days,
+            table: TableState::default().with_selected(0),
+            output: None,
+            scroll: 0,
+            message: String::new(),
+        })
+    }
+
+    fn selected(&self) -> Option<&DayInfo> {
+        self.days.get(self.table.selected()?)
+    }
+
+    /// Runs the selected day, passing `args` on to it, and shows its output.
+    fn run_selected(&mut self, terminal: &mut DefaultTerminal, args: &[&str]) {
+        let Some(index) = self.table.selected() else {
+            return;
+        };
+        let (year, day) = (self.days[index].year, self.days[index].day);
+
+        self.message = format!("Running day {day} of {year}…");
+        let _ = terminal.draw(|f| self.draw(f));
+
+        let output = self
+            .workspace
+            .cargo(year, day, &["run", "--release"])
+            .arg("--")
+            .args(args)
+            .output();
+
+        let text = match output {
+            Ok(o) => {
+                self.message = if o.status.success() {
+                    format!("Day {day} of {year} finished")
+                } else {
+                    format!("Day {day} of {year} failed")
+                };
+                strip_colours(&format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&o.stdout),
+                    String::from_utf8_lossy(&o.stderr)
+                ))
+            }
+            Err(e) => {
+                self.message = format!("Could not run day {day} of {year}");
+                e.to_string()
+            }
+        };
+
+        self.output = Some((format!("Day {day} of {year}"), text));
+        self.scroll = 0;
+
+        match DayInfo::load(&self.workspace, year, day) {
+            Ok(info) => self.days[index] = info,
+            Err(e) => self.message = e,
+        }
+    }
+
+    /// Leaves the dashboard to play the selected day's visualization in the
+    /// terminal, coming back once it finishes.
+    fn visualize_selected(&mut self, terminal: &mut DefaultTerminal) {
+        let Some(d) = self.selected() else {
+            return;
+        };
+        let (year, day) = (d.year, d.day);
+
+        ratatui::restore();
+        let status = self
+            .workspace
+            .cargo(year, day, &["run", "--release"])
+            .args(["--", "--viz"])
+            .status();
+        *terminal = ratatui::init();
+
+        self.message = match status {
+            Ok(s) if s.success() => format!("Day {day} of {year} finished"),
+            Ok(_) => format!("Day {day} of {year} failed"),
+            Err(e) => format!("Could not run day {day} of {year}: {e}"),
+        };
+
+        if let Ok(info) = DayInfo::load(&self.workspace, year, day) {
+            let index = self.table.selected().unwrap_or_default();
+            self.days[index] = info;
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, detail_area, footer_area] = Layout::vertical([
+            Constraint::Min(8),
+            Constraint::Length(10),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let rows = self.days.iter().map(|d| {
+            let last = d.history.last();
+            Row::new(vec![
+                Cell::from(format!("{} {:>2}", d.year, d.day)),
+                d.status().cell(),
+                Cell::from(last.map(|r| short_answer(&r.part1.0)).unwrap_or_default()),
+                Cell::from(last.map(|r| short_answer(&r.part2.0)).unwrap_or_default()),
+                Cell::from(last.map(|r| format_duration(r.total())).unwrap_or_default()),
+                Cell::from(text_sparkline(&d.timings())),
+            ])
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Length(SPARK_RUNS as u16),
+            ],
+        )
+        .header(
+            Row::new(["Day", "Status", "Part 1", "Part 2", "Time", "History"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::bordered().title(" Advent of Code "));
+
+        frame.render_stateful_widget(table, table_area, &mut self.table);
+
+        if let Some((title, text)) = &self.output {
+            let output = Paragraph::new(text.as_str())
+                .scroll((self.scroll, 0))
+                .block(Block::bordered().title(format!(" {title} ")));
+            frame.render_widget(output, detail_area);
+        } else if let Some(d) = self.selected() {
+            let [answers_area, spark_area] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(detail_area);
+
+            let answers = match d.history.last() {
+                Some(r) => Text::from(vec![
+                    Line::from(format!("Parsing: {}", format_duration(r.parse))),
+                    Line::from(format!(
+                        "Part 1: {} ({})",
+                        r.part1.0,
+                        format_duration(r.part1.1)
+                    )),
+                    Line::from(format!(
+                        "Part 2: {} ({})",
+                        r.part2.0.trim_end(),
+                        format_duration(r.part2.1)
+                    )),
+                ]),
+                None => Text::from("No runs recorded"),
+            };
+            frame.render_widget(
+                Paragraph::new(answers).block(Block::bordered().title(" Last run ")),
+                answers_area,
+            );
+
+            let timings = d.timings();
+            let sparkline = Sparkline::default()
+                .data(&timings[timings.len().saturating_sub(spark_area.width as usize)..])
+                .style(Style::default().fg(Color::Yellow))
+                .block(Block::bordered().title(" Timing history "));
+            frame.render_widget(sparkline, spark_area);
+        }
+
+        let help = "↑/↓ select  enter run  v visualize  esc close output  q quit";
+        frame.render_widget(
+            Line::from(vec![
+                help.dark_gray(),
+                "  ".into(),
+                self.message.clone().yellow(),
+            ]),
+            footer_area,
+        );
+    }
+
+    fn handle_key(&mut self, terminal: &mut DefaultTerminal, code: KeyCode) -> bool {
+        let last = self.days.len().saturating_sub(1);
+
+        match code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Esc if self.output.is_some() => self.output = None,
+            KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.table.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.table
+                    .select(Some(self.table.selected().map_or(0, |i| (i + 1).min(last))));
+            }
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(5),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(5),
+            KeyCode::Enter | KeyCode::Char('r') => self.run_selected(terminal, &[]),
+            KeyCode::Char('v') => self.visualize_selected(terminal),
+            _ => {}
+        }
+
+        true
+    }
+}
+
+/// Shows every day in the workspace with its status, last answers and timing
+/// history, and runs the selected day on request.
+pub fn dashboard(workspace: Workspace) -> Result<(), String> {
+    let mut dashboard = Dashboard::new(workspace)?;
+    let mut terminal = ratatui::init();
+
+    let result = loop {
+        if let Err(e) = terminal.draw(|f| dashboard.draw(f)) {
+            break Err(e.to_string());
+        }
+
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                if !dashboard.handle_key(&mut terminal, key.code) {
+                    break Ok(());
+                }
+            }
+            Ok(_) => {}
+            Err(e) => break Err(e.to_string()),
+        }
+    };
+
+    ratatui::restore();
+
+    result
+}
@@ -0,0 +1,79 @@
+use owo_colors::OwoColorize;
+
+mod dashboard;
+mod scaffold;
+mod watch;
+mod workspace;
+
+use workspace::Workspace;
+
+const USAGE: &str = "Usage:
+    aoc run [year] <day> [args...]
+    aoc new [year] <day>
+    aoc watch [year] <day>
+    aoc dashboard";
+
+const DEFAULT_YEAR: u16 = 2022;
+
+/// Parses `[year] <day>` from the start of `args`, returning the remaining
+/// arguments as well.
+fn parse_day(args: &[String]) -> Result<(u16, u8, &[String]), String> {
+    let number = |s: &String| s.parse::<u16>().map_err(|_| format!("Invalid day {s}"));
+
+    let (year, day, rest) = match args {
+        [year, day, rest @ ..] if number(year)? > 25 => (number(year)?, number(day)?, rest),
+        [day, rest @ ..] => (DEFAULT_YEAR, number(day)?, rest),
+        [] => return Err(USAGE.to_owned()),
+    };
+
+    let day = day.try_into().map_err(|_| format!("Invalid day {day}"))?;
+
+    Ok((year, day, rest))
+}
+
+fn run(workspace: &Workspace, year: u16, day: u8, args: &[String]) -> Result<(), String> {
+    workspace.ensure_day(year, day)?;
+
+    let status = workspace
+        .cargo(year, day, &["run", "--release"])
+        .arg("--")
+        .args(args)
+        .status()
+        .map_err(|e| e.to_string())?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Day {day} of {year} failed"))
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result =
+        Workspace::find().and_then(|mut workspace| match args.first().map(String::as_str) {
+            Some("run") => {
+                let (year, day, rest) = parse_day(&args[1..])?;
+                run(&workspace, year, day, rest)
+            }
+            Some("watch") => {
+                let (year, day, _) = parse_day(&args[1..])?;
+                workspace.ensure_day(year, day)?;
+                watch::watch(&workspace, year, day)
+            }
+            Some("dashboard") => dashboard::dashboard(workspace),
+            Some("new") => {
+                let (year, day, _) = parse_day(&args[1..])?;
+                scaffold::new_day(&mut workspace, year, day)?;
+                println!("Created {}", Workspace::day_dir(year, day));
+                Ok(())
+            }
+            _ => Err(USAGE.to_owned()),
+        });
+
+    if let Err(e) = result {
+        eprintln!("{}", e.bright_red());
+        std::process::exit(1);
+    }
+}
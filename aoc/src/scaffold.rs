@@ -0,0 +1,108 @@
+use crate::workspace::Workspace;
+
+const CARGO_TOML: &str = r#"[package]
+name = "PACKAGE"
+version = "0.1.0"
+edition = "2021"
+
+# See more keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+[dependencies]
+util = { path = "UTIL" }
+"#;
+
+const MAIN_RS: &str = r#"use util::*;
+
+struct DayNN;
+
+impl Day for DayNN {
+    fn parse_input(_input: &str) -> Self {
+        todo!()
+    }
+
+    fn part1(&self) -> String {
+        todo!()
+    }
+
+    fn part2(&self) -> String {
+        todo!()
+    }
+
+    fn number() -> u8 {
+        N
+    }
+YEAR}
+
+fn main() {
+    DayNN::run();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "example not filled in yet"]
+    fn example() {
+        let d = DayNN::parse_input(EXAMPLE);
+
+        assert_eq!(d.part1(), "");
+        assert_eq!(d.part2(), "");
+    }
+}
+"#;
+
+fn fill(template: &str, year: u16, day: u8) -> String {
+    let (package, util, year_fn) = if year == 2022 {
+        (format!("day{day:02}"), "../util", String::new())
+    } else {
+        (
+            format!("day{day:02}-{year}"),
+            "../../util",
+            format!("\n    fn year() -> u16 {{\n        {year}\n    }}\n"),
+        )
+    };
+
+    template
+        .replace("PACKAGE", &package)
+        .replace("UTIL", util)
+        .replace("YEAR", &year_fn)
+        .replace("NN", &format!("{day:02}"))
+        .replace("        N\n", &format!("        {day}\n"))
+}
+
+/// Creates the crate for a day with a stub solution, an empty input file and
+/// a workspace entry.
+pub fn new_day(workspace: &mut Workspace, year: u16, day: u8) -> Result<(), String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("There is no day {day}"));
+    }
+    if year < 2015 {
+        return Err(format!("There is no Advent of Code {year}"));
+    }
+
+    let name = Workspace::day_dir(year, day);
+    let dir = workspace.path(&name);
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+
+    let write = |path: std::path::PathBuf, contents: &str| {
+        std::fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| std::fs::write(&path, contents))
+            .map_err(|e| format!("Could not write {}: {e}", path.display()))
+    };
+
+    write(dir.join("Cargo.toml"), &fill(CARGO_TOML, year, day))?;
+    write(dir.join("src/main.rs"), &fill(MAIN_RS, year, day))?;
+    write(dir.join(".gitignore"), "/target\n")?;
+
+    let input = workspace.path(format!("input/{year}/{day:02}"));
+    if !input.exists() {
+        write(input, "")?;
+    }
+
+    workspace.add_member(&name)
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use owo_colors::OwoColorize;
+
+use crate::workspace::Workspace;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+fn add_files(path: &Path, snapshot: &mut Snapshot) {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return;
+    };
+
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(path).into_iter().flatten().flatten() {
+            add_files(&entry.path(), snapshot);
+        }
+    } else if let Ok(modified) = metadata.modified() {
+        snapshot.insert(path.to_owned(), modified);
+    }
+}
+
+/// Modification times of the day's sources, its input and any example files
+/// next to the input named `NN.*`.
+fn snapshot(workspace: &Workspace, year: u16, day: u8) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    let dir = workspace.path(Workspace::day_dir(year, day));
+
+    add_files(&dir.join("src"), &mut snapshot);
+    add_files(&dir.join("Cargo.toml"), &mut snapshot);
+
+    let inputs = workspace.path(format!("input/{year}"));
+    let name = format!("{day:02}");
+    for entry in std::fs::read_dir(inputs).into_iter().flatten().flatten() {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name == name || file_name.starts_with(&format!("{name}.")) {
+            add_files(&entry.path(), &mut snapshot);
+        }
+    }
+
+    snapshot
+}
+
+pub(crate) fn strip_colours(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+/// Pulls the answers out of the output of `Day::run`.
+fn answers(output: &str) -> [Option<String>; 2] {
+    let output = strip_colours(output);
+
+    [1, 2].map(|part| {
+        let label = format!("Part {part}: ");
+        let start = output.find(&label)? + label.len();
+        let end = start + output[start..].find(", took ")?;
+
+        Some(output[start..end].trim().to_owned())
+    })
+}
+
+fn show_answer(answer: &Option<String>) -> String {
+    answer.clone().unwrap_or_else(|| "none".to_owned())
+}
+
+fn run_once(workspace: &Workspace, year: u16, day: u8) -> Result<[Option<String>; 2], String> {
+    let tests = workspace
+        .cargo(year, day, &["test"])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !tests.success() {
+        println!("{}", "Tests failed".bright_red());
+    }
+
+    let output = workspace
+        .cargo(year, day, &["run", "--release"])
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+
+    Ok(answers(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Re-runs the tests and the solution of a day whenever one of its files
+/// changes, comparing the answers against the previous run.
+pub fn watch(workspace: &Workspace, year: u16, day: u8) -> Result<(), String> {
+    let mut last_snapshot = None;
+    let mut last_answers: Option<[Option<String>; 2]> = None;
+
+    loop {
+        let current = snapshot(workspace, year, day);
+
+        if last_snapshot.as_ref() != Some(&current) {
+            println!("{}", format!("Running day {day} of {year}").bright_yellow());
+            let answers = run_once(workspace, year, day)?;
+
+            if let Some(previous) = &last_answers {
+                for (i, (old, new)) in previous.iter().zip(&answers).enumerate() {
+                    if old == new {
+                        println!("Part {}: unchanged", i + 1);
+                    } else {
+                        println!(
+                            "Part {}: {} -> {}",
+                            i + 1,
+                            show_answer(old).bright_red(),
+                            show_answer(new).bright_green()
+                        );
+                    }
+                }
+            }
+
+            last_answers = Some(answers);
+            last_snapshot = Some(current);
+            println!("{}", "Waiting for changes".bright_yellow());
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
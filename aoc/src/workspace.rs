@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The workspace root and its `Cargo.toml`, which lists every day as a member.
+pub struct Workspace {
+    pub root: PathBuf,
+    manifest: String,
+}
+
+impl Workspace {
+    /// Finds the workspace by walking up from the current directory.
+    pub fn find() -> Result<Self, String> {
+        let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+
+        for dir in cwd.ancestors() {
+            if let Ok(manifest) = std::fs::read_to_string(dir.join("Cargo.toml")) {
+                if manifest.contains("[workspace]") {
+                    return Ok(Self {
+                        root: dir.to_owned(),
+                        manifest,
+                    });
+                }
+            }
+        }
+
+        Err("Not inside the workspace".to_owned())
+    }
+
+    fn members_span(&self) -> Result<(usize, usize), String> {
+        let start = self
+            .manifest
+            .find("members = [")
+            .ok_or("Workspace has no members list")?
+            + "members = [".len();
+        let end = start
+            + self.manifest[start..]
+                .find(']')
+                .ok_or("Unclosed members list")?;
+
+        Ok((start, end))
+    }
+
+    pub fn members(&self) -> Result<Vec<String>, String> {
+        let (start, end) = self.members_span()?;
+
+        Ok(self.manifest[start..end]
+            .split(',')
+            .map(|m| m.trim().trim_matches('"'))
+            .filter(|m| !m.is_empty())
+            .map(str::to_owned)
+            .collect())
+    }
+
+    /// The `(year, day)` pairs that are workspace members, in order. Days of
+    /// 2022 live in `dayNN` and days of other years in `YYYY/dayNN`.
+    pub fn days(&self) -> Result<Vec<(u16, u8)>, String> {
+        let mut days: Vec<_> = self
+            .members()?
+            .iter()
+            .filter_map(|m| {
+                let (year, day) = m.split_once('/').unwrap_or(("2022", m));
+                Some((year.parse().ok()?, day.strip_prefix("day")?.parse().ok()?))
+            })
+            .collect();
+        days.sort();
+
+        Ok(days)
+    }
+
+    pub fn ensure_day(&self, year: u16, day: u8) -> Result<(), String> {
+        if self.days()?.contains(&(year, day)) {
+            Ok(())
+        } else {
+            Err(format!("Day {day} of {year} is not in the workspace"))
+        }
+    }
+
+    pub fn day_dir(year: u16, day: u8) -> String {
+        if year == 2022 {
+            format!("day{day:02}")
+        } else {
+            format!("{year}/day{day:02}")
+        }
+    }
+
+    /// Adds `member` to the members list, keeping the days sorted after the
+    /// other crates.
+    pub fn add_member(&mut self, member: &str) -> Result<(), String> {
+        let mut members = self.members()?;
+        if members.iter().any(|m| m == member) {
+            return Ok(());
+        }
+
+        members.push(member.to_owned());
+        let (mut days, others): (Vec<_>, Vec<_>) = members.into_iter().partition(|m| {
+            let day = m.split_once('/').map_or(m.as_str(), |(_, d)| d);
+            day.starts_with("day")
+        });
+        days.sort_by_key(|m| {
+            if m.contains('/') {
+                m.clone()
+            } else {
+                format!("2022/{m}")
+            }
+        });
+
+        let list: String = others
+            .iter()
+            .chain(&days)
+            .map(|m| format!("    \"{m}\",\n"))
+            .collect();
+
+        let (start, end) = self.members_span()?;
+        self.manifest
+            .replace_range(start..end, &format!("\n{list}"));
+
+        std::fs::write(self.root.join("Cargo.toml"), &self.manifest).map_err(|e| e.to_string())
+    }
+
+    pub fn path(&self, p: impl AsRef<Path>) -> PathBuf {
+        self.root.join(p)
+    }
+
+    /// A `cargo` invocation of `subcommand` for the given day's crate, run
+    /// from the workspace root.
+    pub fn cargo(&self, year: u16, day: u8, subcommand: &[&str]) -> Command {
+        let manifest = format!("{}/Cargo.toml", Self::day_dir(year, day));
+
+        let mut command = Command::new("cargo");
+        command
+            .current_dir(&self.root)
+            .args(subcommand)
+            .args(["-q", "--manifest-path", &manifest]);
+
+        command
+    }
+}
@@ -1,3 +1,4 @@
+use util::viz::{Frame, Grid, Visualize};
 use util::*;
 
 struct Instruction {
@@ -50,11 +51,57 @@ fn parse_instructions(s: &str) -> Vec<Instruction> {
         .collect()
 }
 
+/// Moves crates one at a time, as the CrateMover 9000 does.
+fn move_9000(state: &mut [Vec<char>], i: &Instruction) {
+    for _ in 0..i.count {
+        if let Some(c) = state[i.from - 1].pop() {
+            state[i.to - 1].push(c);
+        }
+    }
+}
+
+/// Draws the stacks the way the puzzle input does.
+fn draw(state: &[Vec<char>]) -> Grid<char> {
+    let height = state.iter().map(Vec::len).max().unwrap_or(0);
+    let mut s = String::new();
+
+    for row in (0..height).rev() {
+        let line: Vec<String> = state
+            .iter()
+            .map(|stack| match stack.get(row) {
+                Some(c) => format!("[{c}]"),
+                None => "   ".to_owned(),
+            })
+            .collect();
+        s.push_str(&line.join(" "));
+        s.push('\n');
+    }
+
+    let numbers: Vec<String> = (1..=state.len()).map(|n| format!(" {n} ")).collect();
+    s.push_str(&numbers.join(" "));
+
+    Grid::from_lines(&s)
+}
+
 struct Day05 {
     stacks: Vec<Vec<char>>,
     instructions: Vec<Instruction>,
 }
 
+impl Visualize for Day05 {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut state = self.stacks.clone();
+        let first = Frame::Text(draw(&state));
+
+        Box::new(
+            std::iter::once(first).chain(self.instructions.iter().map(move |i| {
+                move_9000(&mut state, i);
+                Frame::Text(draw(&state))
+            })),
+        )
+    }
+}
+
 impl Day for Day05 {
     fn parse_input(input: &str) -> Self {
         let [layout, instructions]: [&str; 2] = input
@@ -76,11 +123,7 @@ impl Day for Day05 {
         let mut state = self.stacks.clone();
 
         for i in &self.instructions {
-            for _ in 0..i.count {
-                if let Some(c) = state[i.from - 1].pop() {
-                    state[i.to - 1].push(c);
-                }
-            }
+            move_9000(&mut state, i);
         }
 
         state.iter().filter_map(|v| v.last()).collect()
@@ -107,6 +150,10 @@ impl Day for Day05 {
     fn number() -> u8 {
         5
     }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
 }
 
 fn main() {
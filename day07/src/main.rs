@@ -1,6 +1,7 @@
-use std::cell::Cell;
 use std::collections::HashMap;
+use std::path::Path;
 
+use owo_colors::OwoColorize;
 use util::*;
 
 #[allow(non_camel_case_types)]
@@ -13,134 +14,484 @@ enum Terminal {
     file { name: String, size: usize },
 }
 
-#[derive(Debug)]
-enum Type {
-    Folder(Cell<usize>),
-    File(Cell<usize>),
-}
+/// Parses a line of the transcript, or returns `None` if it is not a
+/// command or output this filesystem knows.
+fn parse_line(l: &str) -> Option<Terminal> {
+    let mut ws = l.split_whitespace();
+    let line = match (ws.next()?, ws.next()?, ws.next()) {
+        ("$", "ls", None) => Terminal::ls,
+        ("$", "cd", Some("/")) => Terminal::cd_home,
+        ("$", "cd", Some("..")) => Terminal::cd_up,
+        ("$", "cd", Some(p)) => Terminal::cd { path: p.to_owned() },
+        ("dir", p, None) => Terminal::dir { path: p.to_owned() },
+        (s, p, None) => Terminal::file {
+            name: p.to_owned(),
+            size: s.parse().ok()?,
+        },
+        _ => return None,
+    };
 
-impl Type {
-    fn get_size(&self) -> &Cell<usize> {
-        match self {
-            Type::Folder(s) => s,
-            Type::File(s) => s,
-        }
+    match ws.next() {
+        Some(_) => None,
+        None => Some(line),
     }
 }
 
-struct Day07 {
-    lines: Vec<Terminal>,
+/// Parses every line of a transcript along with its line number.
+fn parse_transcript(input: &str) -> Result<Vec<(usize, Terminal)>, Issue> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            parse_line(l).map(|t| (i + 1, t)).ok_or_else(|| Issue {
+                line: i + 1,
+                kind: IssueKind::Unsupported(l.to_owned()),
+            })
+        })
+        .collect()
 }
 
-fn get_filesystem(lines: &[Terminal]) -> HashMap<Vec<String>, Type> {
-    let mut pwd = Vec::new();
-    let mut fs = HashMap::new();
-    fs.insert(pwd.clone(), Type::Folder(Cell::new(0)));
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum IssueKind {
+    Unsupported(String),
+    /// A `cd` into a folder no `ls` has shown.
+    CdUnlisted(String),
+    CdIntoFile(String),
+    CdUpAtRoot,
+    /// A listed entry with no `ls` before it.
+    OutputWithoutLs,
+    SizeChanged {
+        name: String,
+        was: usize,
+        now: usize,
+    },
+    /// An entry listed as a file in one place and a folder in another.
+    KindChanged(String),
+}
 
-    for l in lines {
-        match l {
-            Terminal::cd_up => {
-                pwd.pop();
-            }
-            Terminal::cd_home => {
-                pwd = Vec::new();
+/// Something wrong with a transcript, with the line number it is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Issue {
+    line: usize,
+    kind: IssueKind,
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            IssueKind::Unsupported(l) => write!(f, "unsupported line `{l}`"),
+            IssueKind::CdUnlisted(p) => write!(f, "cd into `{p}`, which was never listed"),
+            IssueKind::CdIntoFile(p) => write!(f, "cd into `{p}`, which is a file"),
+            IssueKind::CdUpAtRoot => write!(f, "cd .. from the root folder"),
+            IssueKind::OutputWithoutLs => write!(f, "listing without an ls before it"),
+            IssueKind::SizeChanged { name, was, now } => {
+                write!(f, "`{name}` was listed with size {was}, now {now}")
             }
-            Terminal::cd { path } => {
-                pwd.push(path.clone());
+            IssueKind::KindChanged(name) => {
+                write!(f, "`{name}` was listed as both a file and a folder")
             }
-            Terminal::ls => {}
-            Terminal::dir { path } => {
-                let mut full_path = pwd.clone();
-                full_path.push(path.clone());
-                fs.insert(full_path.clone(), Type::Folder(Cell::new(0)));
+        }
+    }
+}
+
+impl std::error::Error for Issue {}
+
+#[derive(Debug)]
+enum Kind {
+    /// A directory and its entries by name.
+    Folder(HashMap<String, usize>),
+    File,
+}
+
+#[derive(Debug)]
+struct Node {
+    name: String,
+    parent: Option<usize>,
+    kind: Kind,
+    /// The size of a file, or the total size of everything in a folder.
+    size: usize,
+}
+
+impl Node {
+    fn is_folder(&self) -> bool {
+        matches!(self.kind, Kind::Folder(_))
+    }
+}
+
+/// The filesystem as a tree of nodes indexed into a single arena, with the
+/// root folder first.
+struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl FileSystem {
+    const ROOT: usize = 0;
+
+    /// Rebuilds the filesystem from a transcript, along with everything
+    /// inconsistent in it. Entries are kept as first listed, and a `cd` into
+    /// an unlisted folder adds it.
+    fn build(lines: &[(usize, Terminal)]) -> (Self, Vec<Issue>) {
+        let mut fs = Self {
+            nodes: vec![Node {
+                name: "/".to_owned(),
+                parent: None,
+                kind: Kind::Folder(HashMap::new()),
+                size: 0,
+            }],
+        };
+        let mut issues = Vec::new();
+        let mut issue = |line: usize, kind| issues.push(Issue { line, kind });
+
+        let mut pwd = Self::ROOT;
+        let mut listing = false;
+        for (line, l) in lines {
+            let line = *line;
+            listing &= matches!(l, Terminal::dir { .. } | Terminal::file { .. });
+
+            match l {
+                Terminal::cd_up => match fs.nodes[pwd].parent {
+                    Some(parent) => pwd = parent,
+                    None => issue(line, IssueKind::CdUpAtRoot),
+                },
+                Terminal::cd_home => pwd = Self::ROOT,
+                Terminal::cd { path } => match fs.child(pwd, path) {
+                    Some(i) if fs.nodes[i].is_folder() => pwd = i,
+                    Some(_) => issue(line, IssueKind::CdIntoFile(path.clone())),
+                    None => {
+                        issue(line, IssueKind::CdUnlisted(path.clone()));
+                        pwd = fs.add(pwd, path, Kind::Folder(HashMap::new()), 0);
+                    }
+                },
+                Terminal::ls => listing = true,
+                Terminal::dir { path } => {
+                    if !listing {
+                        issue(line, IssueKind::OutputWithoutLs);
+                    }
+                    match fs.child(pwd, path) {
+                        Some(i) if fs.nodes[i].is_folder() => {}
+                        Some(_) => issue(line, IssueKind::KindChanged(path.clone())),
+                        None => {
+                            fs.add(pwd, path, Kind::Folder(HashMap::new()), 0);
+                        }
+                    }
+                }
+                Terminal::file { name, size } => {
+                    if !listing {
+                        issue(line, IssueKind::OutputWithoutLs);
+                    }
+                    match fs.child(pwd, name) {
+                        Some(i) if fs.nodes[i].is_folder() => {
+                            issue(line, IssueKind::KindChanged(name.clone()))
+                        }
+                        Some(i) if fs.nodes[i].size != *size => issue(
+                            line,
+                            IssueKind::SizeChanged {
+                                name: name.clone(),
+                                was: fs.nodes[i].size,
+                                now: *size,
+                            },
+                        ),
+                        Some(_) => {}
+                        None => {
+                            fs.add(pwd, name, Kind::File, *size);
+                        }
+                    }
+                }
             }
-            Terminal::file { name, size } => {
-                let mut path = pwd.clone();
-                path.push(name.clone());
-                fs.insert(path, Type::File(Cell::new(*size)));
+        }
+
+        // Every node comes after its parent, so going backwards adds each
+        // node's size to its parent only once the node's own size is known.
+        for i in (1..fs.nodes.len()).rev() {
+            if let Some(parent) = fs.nodes[i].parent {
+                fs.nodes[parent].size += fs.nodes[i].size;
             }
         }
+
+        (fs, issues)
+    }
+
+    fn child(&self, folder: usize, name: &str) -> Option<usize> {
+        match &self.nodes[folder].kind {
+            Kind::Folder(entries) => entries.get(name).copied(),
+            Kind::File => None,
+        }
+    }
+
+    /// Adds an entry to a folder, returning its index.
+    fn add(&mut self, parent: usize, name: &str, kind: Kind, size: usize) -> usize {
+        let index = self.nodes.len();
+        if let Kind::Folder(entries) = &mut self.nodes[parent].kind {
+            entries.insert(name.to_owned(), index);
+        }
+
+        self.nodes.push(Node {
+            name: name.to_owned(),
+            parent: Some(parent),
+            kind,
+            size,
+        });
+
+        index
+    }
+
+    fn folders(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(|&i| self.nodes[i].is_folder())
+    }
+
+    fn used(&self) -> usize {
+        self.nodes[Self::ROOT].size
+    }
+
+    /// The folders with a total size of at most `limit`.
+    fn folders_at_most(&self, limit: usize) -> impl Iterator<Item = usize> + '_ {
+        self.folders().filter(move |&i| self.nodes[i].size <= limit)
+    }
+
+    /// The folders with a total size above `limit`.
+    fn folders_larger_than(&self, limit: usize) -> impl Iterator<Item = usize> + '_ {
+        self.folders().filter(move |&i| self.nodes[i].size > limit)
+    }
+
+    /// The smallest folder to delete so that a disk of `disk` bytes has at
+    /// least `wanted` bytes free.
+    fn smallest_freeing(&self, disk: usize, wanted: usize) -> Option<usize> {
+        let needed = wanted.saturating_sub(disk.saturating_sub(self.used()));
+
+        self.folders_at_least(needed)
+            .min_by_key(|&i| self.nodes[i].size)
+    }
+
+    fn folders_at_least(&self, limit: usize) -> impl Iterator<Item = usize> + '_ {
+        self.folders().filter(move |&i| self.nodes[i].size >= limit)
+    }
+
+    /// The node at an absolute path like `/a/e`.
+    fn lookup(&self, path: &str) -> Option<usize> {
+        path.split('/')
+            .filter(|p| !p.is_empty())
+            .try_fold(Self::ROOT, |node, name| self.child(node, name))
+    }
+
+    fn path(&self, mut node: usize) -> String {
+        let mut names = Vec::new();
+        while let Some(parent) = self.nodes[node].parent {
+            names.push(self.nodes[node].name.as_str());
+            node = parent;
+        }
+        names.reverse();
+
+        format!("/{}", names.join("/"))
     }
 
-    for (path, t) in &fs {
-        if let Type::File(size) = t {
-            let mut parent = path.clone();
-            while !parent.is_empty() {
-                parent.pop();
-                let (_, folder) = fs.iter().find(|&(p, _)| p == &parent).unwrap();
-                folder.get_size().set(folder.get_size().get() + size.get());
+    /// The puzzle's indented listing of the tree, with entries sorted by
+    /// name.
+    fn tree(&self) -> String {
+        let mut listing = String::new();
+        let mut stack = vec![(Self::ROOT, 0)];
+
+        while let Some((i, depth)) = stack.pop() {
+            let node = &self.nodes[i];
+            let indent = "  ".repeat(depth);
+            match &node.kind {
+                Kind::Folder(entries) => {
+                    listing.push_str(&format!("{indent}- {} (dir)\n", node.name));
+
+                    let mut children: Vec<_> = entries.iter().collect();
+                    children.sort_unstable_by(|a, b| b.0.cmp(a.0));
+                    stack.extend(children.into_iter().map(|(_, &c)| (c, depth + 1)));
+                }
+                Kind::File => listing.push_str(&format!(
+                    "{indent}- {} (file, size={})\n",
+                    node.name, node.size
+                )),
             }
         }
+
+        listing
+    }
+
+    /// A `du -h` style report of every folder, largest first.
+    fn du(&self) -> String {
+        let mut folders: Vec<_> = self
+            .folders()
+            .map(|i| (self.nodes[i].size, self.path(i)))
+            .collect();
+        folders.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        folders
+            .into_iter()
+            .map(|(size, path)| format!("{:>6}  {path}\n", human_size(size)))
+            .collect()
+    }
+}
+
+/// Writes a transcript of exploring a real directory with `cd` and `ls`,
+/// skipping symbolic links.
+fn generate(dir: &Path) -> std::io::Result<String> {
+    let mut transcript = "$ cd /\n".to_owned();
+    list(dir, &mut transcript)?;
+
+    Ok(transcript)
+}
+
+fn list(dir: &Path, transcript: &mut String) -> std::io::Result<()> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_symlink() {
+            continue;
+        }
+
+        let name = entry
+            .file_name()
+            .into_string()
+            .ok()
+            .filter(|n| !n.contains(char::is_whitespace))
+            .ok_or_else(|| {
+                let message = format!(
+                    "{} cannot be written in a transcript",
+                    entry.path().display()
+                );
+                std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+            })?;
+        entries.push((name, metadata));
+    }
+    entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+    transcript.push_str("$ ls\n");
+    for (name, metadata) in &entries {
+        if metadata.is_dir() {
+            transcript.push_str(&format!("dir {name}\n"));
+        } else {
+            transcript.push_str(&format!("{} {name}\n", metadata.len()));
+        }
+    }
+
+    for (name, metadata) in &entries {
+        if metadata.is_dir() {
+            transcript.push_str(&format!("$ cd {name}\n"));
+            list(&dir.join(name), transcript)?;
+            transcript.push_str("$ cd ..\n");
+        }
+    }
+
+    Ok(())
+}
+
+/// The total size of the files under a real directory, skipping symbolic
+/// links.
+fn disk_usage(dir: &Path) -> std::io::Result<usize> {
+    let mut total = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+
+        if metadata.is_dir() {
+            total += disk_usage(&entry.path())?;
+        } else if !metadata.is_symlink() {
+            total += metadata.len() as usize;
+        }
+    }
+
+    Ok(total)
+}
+
+/// A size with a binary unit suffix, the way `du -h` writes it.
+fn human_size(size: usize) -> String {
+    let mut value = size as f64;
+    let mut units = ["K", "M", "G", "T", "P"].iter();
+    let mut unit = "";
+
+    while value >= 1024.0 {
+        match units.next() {
+            Some(u) => unit = u,
+            None => break,
+        }
+        value /= 1024.0;
+    }
+
+    match unit {
+        "" => size.to_string(),
+        _ if value < 10.0 => format!("{value:.1}{unit}"),
+        _ => format!("{value:.0}{unit}"),
     }
+}
 
-    fs
+/// The sizes the puzzle asks about.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Limits {
+    /// The largest size of the folders summed in part 1.
+    small: usize,
+    disk: usize,
+    /// The free space needed for the update in part 2.
+    wanted: usize,
+}
+
+impl Limits {
+    /// The puzzle's sizes, overridden by `--small N`, `--disk N` and
+    /// `--wanted N` on the command line.
+    fn from_args(args: &[String]) -> Result<Self, String> {
+        let limits = Self::default();
+
+        Ok(Self {
+            small: args::parse(args, "--small")?.unwrap_or(limits.small),
+            disk: args::parse(args, "--disk")?.unwrap_or(limits.disk),
+            wanted: args::parse(args, "--wanted")?.unwrap_or(limits.wanted),
+        })
+    }
+}
+
+impl Default for Limits {
+    /// The sizes from the puzzle.
+    fn default() -> Self {
+        Self {
+            small: 100_000,
+            disk: 70_000_000,
+            wanted: 30_000_000,
+        }
+    }
+}
+
+struct Day07 {
+    fs: FileSystem,
+    limits: Limits,
 }
 
 impl Day for Day07 {
     fn parse_input(input: &str) -> Self {
-        let lines = input
-            .lines()
-            .map(|l| {
-                let mut ws = l.split_whitespace();
-                match (ws.next().unwrap(), ws.next().unwrap(), ws.next()) {
-                    ("$", "ls", None) => Terminal::ls,
-                    ("$", "cd", Some("/")) => Terminal::cd_home,
-                    ("$", "cd", Some("..")) => Terminal::cd_up,
-                    ("$", "cd", Some(p)) => Terminal::cd { path: p.to_owned() },
-                    ("dir", p, None) => Terminal::dir { path: p.to_owned() },
-                    (s, p, None) => Terminal::file {
-                        name: p.to_owned(),
-                        size: s.parse().unwrap(),
-                    },
-                    _ => panic!("Unsupported {l}"),
-                }
-            })
-            .collect();
+        let lines = parse_transcript(input).unwrap_or_else(|e| panic!("Invalid transcript: {e}"));
+        let (fs, _) = FileSystem::build(&lines);
+
+        Self {
+            fs,
+            limits: Limits::default(),
+        }
+    }
+
+    fn configure(&mut self, args: &[String]) -> Result<(), String> {
+        self.limits = Limits::from_args(args)?;
 
-        Self { lines }
+        Ok(())
     }
 
     fn part1(&self) -> String {
-        let fs = get_filesystem(&self.lines);
-
-        fs.iter()
-            .filter_map(|(_, t)| {
-                if let Type::Folder(s) = t {
-                    if s.get() <= 100_000 {
-                        Some(s.get())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
+        self.fs
+            .folders_at_most(self.limits.small)
+            .map(|i| self.fs.nodes[i].size)
             .sum::<usize>()
             .to_string()
     }
 
     fn part2(&self) -> String {
-        let fs = get_filesystem(&self.lines);
-
-        let total_used = fs.get(&Vec::new()).unwrap().get_size().get();
-        let total_free = 70_000_000 - total_used;
-        let needed = 30_000_000 - total_free;
-
-        fs.iter()
-            .filter_map(|(_, t)| {
-                if let Type::Folder(s) = t {
-                    if s.get() >= needed {
-                        Some(s.get())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .min()
-            .unwrap()
-            .to_string()
+        let folder = self
+            .fs
+            .smallest_freeing(self.limits.disk, self.limits.wanted)
+            .expect("No folder is large enough");
+
+        self.fs.nodes[folder].size.to_string()
     }
 
     fn number() -> u8 {
@@ -148,6 +499,275 @@ impl Day for Day07 {
     }
 }
 
+/// Checks the sizes worked out from a transcript of a real directory
+/// against the sizes of the directory itself, printing any that differ.
+fn check(dir: &Path) -> std::io::Result<()> {
+    let transcript = generate(dir)?;
+    let lines = parse_transcript(&transcript).map_err(std::io::Error::other)?;
+    let (fs, issues) = FileSystem::build(&lines);
+    if let Some(issue) = issues.first() {
+        return Err(std::io::Error::other(issue.to_string()));
+    }
+
+    let mut mismatches = 0;
+    for i in fs.folders() {
+        let path = fs.path(i);
+        let real = disk_usage(&dir.join(path.trim_start_matches('/')))?;
+        if real != fs.nodes[i].size {
+            println!(
+                "{path}: {} from the transcript, {real} on disk",
+                fs.nodes[i].size
+            );
+            mismatches += 1;
+        }
+    }
+
+    println!(
+        "{} of {} folders match",
+        fs.folders().count() - mismatches,
+        fs.folders().count()
+    );
+
+    Ok(())
+}
+
+/// Runs a tool instead of solving the puzzle, when asked for one:
+/// * `--tree`, `--du`, `--larger-than N` and `--find <path>` report on the
+///   puzzle input's filesystem.
+/// * `--validate` lists everything inconsistent in the puzzle input.
+/// * `--generate <dir>` writes a transcript of exploring a real directory.
+/// * `--check <dir>` compares the sizes from that transcript with the
+///   directory's.
+fn report(args: &[String]) -> Option<Result<(), String>> {
+    [
+        "--tree",
+        "--du",
+        "--larger-than",
+        "--find",
+        "--validate",
+        "--generate",
+        "--check",
+    ]
+    .into_iter()
+    .any(|name| args::flag(args, name))
+    .then(|| run_report(args))
+}
+
+fn run_report(args: &[String]) -> Result<(), String> {
+    if let Some(dir) = args::value(args, "--generate")? {
+        let transcript = generate(Path::new(dir))
+            .map_err(|e| format!("Could not generate a transcript: {e}"))?;
+        print!("{transcript}");
+        return Ok(());
+    }
+    if let Some(dir) = args::value(args, "--check")? {
+        return check(Path::new(dir)).map_err(|e| format!("Could not check {dir}: {e}"));
+    }
+
+    let input = input::Inputs::from_env()
+        .get(Day07::year(), Day07::number())
+        .map_err(|e| format!("Could not read input: {e}"))?;
+
+    if args::flag(args, "--validate") {
+        let lines = parse_transcript(&input).map_err(|e| e.to_string())?;
+        let (_, issues) = FileSystem::build(&lines);
+        for issue in &issues {
+            println!("{issue}");
+        }
+        println!("{} issues", issues.len());
+
+        return Ok(());
+    }
+
+    let fs = Day07::parse_input(&input).fs;
+
+    if args::flag(args, "--tree") {
+        print!("{}", fs.tree());
+    }
+    if args::flag(args, "--du") {
+        print!("{}", fs.du());
+    }
+    if let Some(limit) = args::parse(args, "--larger-than")? {
+        for i in fs.folders_larger_than(limit) {
+            println!("{:>10}  {}", fs.nodes[i].size, fs.path(i));
+        }
+    }
+    if let Some(path) = args::value(args, "--find")? {
+        let i = fs.lookup(path).ok_or_else(|| format!("{path} not found"))?;
+        println!("{}: {} bytes", fs.path(i), fs.nodes[i].size);
+    }
+
+    Ok(())
+}
+
 fn main() {
-    Day07::run();
+    let args: Vec<String> = std::env::args().collect();
+
+    match report(&args) {
+        Some(Err(e)) => {
+            eprintln!("{}", e.bright_red());
+            std::process::exit(1);
+        }
+        Some(Ok(())) => {}
+        None => Day07::run(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    fn issues(transcript: &str) -> Vec<Issue> {
+        FileSystem::build(&parse_transcript(transcript).unwrap()).1
+    }
+
+    fn issue(line: usize, kind: IssueKind) -> Vec<Issue> {
+        vec![Issue { line, kind }]
+    }
+
+    #[test]
+    fn example() {
+        let day = Day07::parse_input(EXAMPLE);
+        assert_eq!(day.part1(), "95437");
+        assert_eq!(day.part2(), "24933642");
+
+        let fs = &day.fs;
+        assert_eq!(fs.used(), 48381165);
+        assert_eq!(fs.lookup("/a/e").map(|i| fs.nodes[i].size), Some(584));
+        assert_eq!(fs.lookup("/a/x"), None);
+        assert!(issues(EXAMPLE).is_empty());
+    }
+
+    #[test]
+    fn unsupported_line() {
+        let transcript = "$ cd /\n$ ls\n$ rm -rf a\n";
+
+        assert_eq!(
+            parse_transcript(transcript).err(),
+            Some(Issue {
+                line: 3,
+                kind: IssueKind::Unsupported("$ rm -rf a".to_owned()),
+            })
+        );
+    }
+
+    #[test]
+    fn cd_unlisted() {
+        assert_eq!(
+            issues("$ cd /\n$ ls\ndir a\n$ cd b\n$ ls\n10 f\n"),
+            issue(4, IssueKind::CdUnlisted("b".to_owned()))
+        );
+    }
+
+    #[test]
+    fn cd_into_file() {
+        assert_eq!(
+            issues("$ cd /\n$ ls\n10 a\n$ cd a\n"),
+            issue(4, IssueKind::CdIntoFile("a".to_owned()))
+        );
+    }
+
+    #[test]
+    fn cd_up_at_root() {
+        assert_eq!(
+            issues("$ cd /\n$ ls\ndir a\n$ cd ..\n"),
+            issue(4, IssueKind::CdUpAtRoot)
+        );
+    }
+
+    #[test]
+    fn output_without_ls() {
+        assert_eq!(
+            issues("$ cd /\n$ ls\ndir a\n$ cd a\n10 f\n"),
+            issue(5, IssueKind::OutputWithoutLs)
+        );
+        assert_eq!(issues("dir a\n"), issue(1, IssueKind::OutputWithoutLs));
+    }
+
+    #[test]
+    fn size_changed() {
+        let kind = IssueKind::SizeChanged {
+            name: "f".to_owned(),
+            was: 10,
+            now: 20,
+        };
+
+        assert_eq!(issues("$ cd /\n$ ls\n10 f\n$ ls\n20 f\n"), issue(5, kind));
+        assert!(issues("$ cd /\n$ ls\n10 f\n$ ls\n10 f\n").is_empty());
+    }
+
+    #[test]
+    fn kind_changed() {
+        assert_eq!(
+            issues("$ cd /\n$ ls\n10 a\n$ ls\ndir a\n"),
+            issue(5, IssueKind::KindChanged("a".to_owned()))
+        );
+        assert_eq!(
+            issues("$ cd /\n$ ls\ndir a\n$ ls\n10 a\n"),
+            issue(5, IssueKind::KindChanged("a".to_owned()))
+        );
+    }
+
+    #[test]
+    fn generated_transcript_matches_the_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for folder in ["a/b", "a/empty", "d"] {
+            std::fs::create_dir_all(root.join(folder)).unwrap();
+        }
+        for (file, size) in [("a/f", 100), ("a/b/g", 2000), ("c.txt", 50), ("d/e", 7)] {
+            std::fs::write(root.join(file), vec![b'x'; size]).unwrap();
+        }
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(root.join("a"), root.join("d/link")).unwrap();
+
+        let transcript = generate(root).unwrap();
+        let (fs, issues) = FileSystem::build(&parse_transcript(&transcript).unwrap());
+
+        assert!(issues.is_empty(), "{issues:?}");
+        assert_eq!(fs.used(), 2157);
+        assert_eq!(fs.folders().count(), 5);
+        for i in fs.folders() {
+            let path = fs.path(i);
+            let real = disk_usage(&root.join(path.trim_start_matches('/'))).unwrap();
+            assert_eq!(fs.nodes[i].size, real, "{path}");
+        }
+
+        assert!(check(root).is_ok());
+    }
+
+    #[test]
+    fn names_with_spaces_cannot_be_generated() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("two words"), "x").unwrap();
+
+        let error = generate(dir.path()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
 }
@@ -1,3 +1,7 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+use util::viz::{self, Frame, Rgb};
 use util::*;
 
 #[derive(Debug)]
@@ -5,8 +9,6 @@ struct Grid {
     map: Vec<Vec<u32>>,
 }
 
-type Position = (isize, isize);
-
 impl Grid {
     fn new(input: &str) -> Self {
         let map: Vec<Vec<_>> = input
@@ -16,68 +18,156 @@ impl Grid {
 
         Self { map }
     }
+}
+
+/// Visibility and viewing distance for every tree, indexed by row then
+/// column.
+struct Survey {
+    visible: Vec<Vec<bool>>,
+    scores: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    /// Surveys every tree with one pass along each row and column in both
+    /// directions. A stack holds the trees seen so far that are not hidden
+    /// behind a later tree at least as tall, tallest at the bottom, so the
+    /// first tree left after popping the shorter ones is the one blocking the
+    /// view, and a tree is visible from the edge when none is left.
+    fn survey(&self) -> Survey {
+        let height = self.map.len();
+        let width = self.map.first().map_or(0, Vec::len);
 
-    fn get(&self, (x, y): Position) -> Option<u32> {
-        self.map
-            .get(y as usize)
-            .and_then(|v| v.get(x as usize).copied())
+        let mut survey = Survey {
+            visible: vec![vec![false; width]; height],
+            scores: vec![vec![1; width]; height],
+        };
+
+        let rows = (0..height).map(|y| (0..width).map(|x| (x, y)).collect::<Vec<_>>());
+        let columns = (0..width).map(|x| (0..height).map(|y| (x, y)).collect::<Vec<_>>());
+
+        for mut line in rows.chain(columns) {
+            self.scan(&line, &mut survey);
+            line.reverse();
+            self.scan(&line, &mut survey);
+        }
+
+        survey
     }
 
-    fn positions(&self) -> GridPosIterator {
-        GridPosIterator {
-            position: (0, 0),
-            grid: self,
+    /// Looks back from each tree in `line` towards its start.
+    fn scan(&self, line: &[(usize, usize)], survey: &mut Survey) {
+        let mut stack: Vec<(usize, u32)> = Vec::new();
+
+        for (i, &(x, y)) in line.iter().enumerate() {
+            let tree = self.map[y][x];
+            while stack.last().is_some_and(|&(_, h)| h < tree) {
+                stack.pop();
+            }
+
+            let distance = match stack.last() {
+                Some(&(blocker, _)) => i - blocker,
+                None => {
+                    survey.visible[y][x] = true;
+                    i
+                }
+            };
+            survey.scores[y][x] *= distance;
+
+            stack.push((i, tree));
         }
     }
+}
+
+impl Survey {
+    /// The position and score of the tree with the best scenic score.
+    fn best(&self) -> ((usize, usize), usize) {
+        self.scores
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, &s)| ((x, y), s)))
+            .max_by_key(|&(_, s)| s)
+            .unwrap_or(((0, 0), 0))
+    }
+
+    /// Visible trees in green and hidden ones in grey, brighter the taller
+    /// they are.
+    fn visibility_map(&self, grid: &Grid) -> viz::Grid<Rgb> {
+        self.map_pixels(|x, y| {
+            let shade = 60 + grid.map[y][x] as u8 * 20;
+            if self.visible[y][x] {
+                [shade / 4, shade, shade / 4]
+            } else {
+                [shade / 2, shade / 2, shade / 2]
+            }
+        })
+    }
+
+    /// Every tree coloured by its scenic score, on a logarithmic scale since
+    /// a few trees score far higher than the rest.
+    fn heatmap(&self) -> viz::Grid<Rgb> {
+        let (_, best) = self.best();
+        let top = (best as f64).ln_1p().max(1.0);
+
+        self.map_pixels(|x, y| heat((self.scores[y][x] as f64).ln_1p() / top))
+    }
+
+    fn map_pixels(&self, pixel: impl Fn(usize, usize) -> Rgb) -> viz::Grid<Rgb> {
+        let height = self.scores.len();
+        let width = self.scores.first().map_or(0, Vec::len);
 
-    fn direction_iter(&self, position: Position, direction: Position) -> GridIterator {
-        GridIterator {
-            position,
-            direction,
-            grid: self,
+        let mut map = viz::Grid::new(width, height, [0; 3]);
+        for y in 0..height {
+            for x in 0..width {
+                map.set(x, y, pixel(x, y));
+            }
         }
+
+        map
     }
 }
 
-#[derive(Debug)]
-struct GridPosIterator<'a> {
-    position: Position,
-    grid: &'a Grid,
+/// A colour from black through red and yellow to white for `t` from 0 to 1.
+fn heat(t: f64) -> Rgb {
+    let channel = |from: f64| ((t * 3.0 - from).clamp(0.0, 1.0) * 255.0) as u8;
+
+    [channel(0.0), channel(1.0), channel(2.0)]
 }
 
-impl<'a> Iterator for GridPosIterator<'a> {
-    type Item = (Position, u32);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let x_move = (self.position.0 + 1, self.position.1);
-        let y_move = (0, self.position.1 + 1);
-        if let Some(v) = self.grid.get(x_move) {
-            self.position = x_move;
-            Some((x_move, v))
-        } else if let Some(v) = self.grid.get(y_move) {
-            self.position = y_move;
-            Some((y_move, v))
-        } else {
-            None
+/// Prints the trees' heights coloured by visibility.
+fn print_visibility(grid: &Grid, survey: &Survey) {
+    for (y, row) in grid.map.iter().enumerate() {
+        for (x, height) in row.iter().enumerate() {
+            if survey.visible[y][x] {
+                print!("{}", height.bright_green());
+            } else {
+                print!("{}", height.bright_black());
+            }
         }
+        println!();
     }
 }
 
-#[derive(Debug)]
-struct GridIterator<'a> {
-    position: Position,
-    direction: Position,
-    grid: &'a Grid,
-}
+/// Prints the heatmap with the best tree marked.
+fn print_heatmap(survey: &Survey) {
+    let heatmap = survey.heatmap();
+    let (best, _) = survey.best();
 
-impl<'a> Iterator for GridIterator<'a> {
-    type Item = u32;
+    for (y, row) in heatmap.rows().enumerate() {
+        for (x, &[r, g, b]) in row.iter().enumerate() {
+            let c = if (x, y) == best { '*' } else { '█' };
+            print!("{}", c.truecolor(r, g, b));
+        }
+        println!();
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.position.0 += self.direction.0;
-        self.position.1 += self.direction.1;
+/// Writes a map as a PNG or, for a `.ppm` path, a PPM image.
+fn write_map(path: &Path, map: viz::Grid<Rgb>) -> std::io::Result<()> {
+    let frame = Frame::Pixels(map);
 
-        self.grid.get(self.position)
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("ppm") => viz::write_ppm(path, &frame, 4),
+        _ => viz::write_png(path, &frame, 4),
     }
 }
 
@@ -93,55 +183,245 @@ impl Day for Day08 {
     }
 
     fn part1(&self) -> String {
-        let directions = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+        self.grid
+            .survey()
+            .visible
+            .iter()
+            .flatten()
+            .filter(|&&v| v)
+            .count()
+            .to_string()
+    }
 
-        let mut valid_trees = Vec::new();
+    fn part2(&self) -> String {
+        self.grid
+            .survey()
+            .scores
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .to_string()
+    }
 
-        for (pos, value) in self.grid.positions() {
-            let valid = directions
-                .iter()
-                .any(|&dir| self.grid.direction_iter(pos, dir).all(|c| c < value));
+    fn number() -> u8 {
+        8
+    }
+}
+
+/// Shows the maps asked for with `--visibility [image]` and
+/// `--heatmap [image]`, in the terminal or as an image, instead of solving
+/// the puzzle.
+fn maps(args: &[String]) -> Option<Result<(), String>> {
+    let visibility = args::optional(args, "--visibility");
+    let heatmap = args::optional(args, "--heatmap");
+    if visibility.is_none() && heatmap.is_none() {
+        return None;
+    }
 
-            if valid {
-                valid_trees.push(pos);
+    Some(show_maps(visibility, heatmap))
+}
+
+/// Prints each map without a path and writes each one with a path.
+fn show_maps(
+    visibility: Option<Option<&str>>,
+    heatmap: Option<Option<&str>>,
+) -> Result<(), String> {
+    let input = input::Inputs::from_env()
+        .get(Day08::year(), Day08::number())
+        .map_err(|e| format!("Could not read input: {e}"))?;
+    let grid = Day08::parse_input(&input).grid;
+    let survey = grid.survey();
+
+    let write = |path: &str, map| {
+        write_map(Path::new(path), map).map_err(|e| format!("Could not write {path}: {e}"))
+    };
+
+    match visibility {
+        Some(Some(path)) => write(path, survey.visibility_map(&grid))?,
+        Some(None) => print_visibility(&grid, &survey),
+        None => {}
+    }
+    match heatmap {
+        Some(Some(path)) => write(path, survey.heatmap())?,
+        Some(None) => print_heatmap(&survey),
+        None => {}
+    }
+
+    let ((x, y), score) = survey.best();
+    println!("Best tree at {x},{y} with a scenic score of {score}");
+
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    match maps(&args) {
+        Some(Err(e)) => {
+            eprintln!("{}", e.bright_red());
+            std::process::exit(1);
+        }
+        Some(Ok(())) => {}
+        None => Day08::run(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    type Position = (isize, isize);
+
+    const EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390";
+
+    /// The original solution, which walks a ray in every direction from every
+    /// tree, kept as a reference for [`Grid::survey`].
+    impl Grid {
+        fn get(&self, (x, y): Position) -> Option<u32> {
+            self.map
+                .get(y as usize)
+                .and_then(|v| v.get(x as usize).copied())
+        }
+
+        /// Every tree with its height, row by row.
+        fn positions(&self) -> GridPosIterator<'_> {
+            // Starts just before the first tree, since the iterator moves first.
+            GridPosIterator {
+                position: (-1, 0),
+                grid: self,
             }
         }
 
-        valid_trees.len().to_string()
-    }
+        fn direction_iter(&self, position: Position, direction: Position) -> GridIterator<'_> {
+            GridIterator {
+                position,
+                direction,
+                grid: self,
+            }
+        }
 
-    fn part2(&self) -> String {
-        let directions = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-
-        let mut scores = Vec::new();
-        for (pos, value) in self.grid.positions() {
-            let score: usize = directions
-                .iter()
-                .map(|&dir| {
-                    let mut score = 0;
-                    for v in self.grid.direction_iter(pos, dir) {
-                        if v < value {
-                            score += 1;
-                        } else {
-                            score += 1;
-                            break;
-                        }
-                    }
-                    score
+        /// The number of visible trees, looking along every ray one tree at a
+        /// time.
+        fn reference_visible(&self) -> usize {
+            let directions = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+            self.positions()
+                .filter(|&(pos, value)| {
+                    directions
+                        .iter()
+                        .any(|&dir| self.direction_iter(pos, dir).all(|c| c < value))
                 })
-                .product();
+                .count()
+        }
+
+        /// The best scenic score, looking along every ray one tree at a time.
+        fn reference_best_score(&self) -> usize {
+            let directions = [(0, -1), (1, 0), (0, 1), (-1, 0)];
 
-            scores.push(score);
+            self.positions()
+                .map(|(pos, value)| {
+                    directions
+                        .iter()
+                        .map(|&dir| {
+                            let mut score = 0;
+                            for v in self.direction_iter(pos, dir) {
+                                score += 1;
+                                if v >= value {
+                                    break;
+                                }
+                            }
+                            score
+                        })
+                        .product()
+                })
+                .max()
+                .unwrap_or(0)
         }
+    }
 
-        scores.iter().max().unwrap().to_string()
+    #[derive(Debug)]
+    struct GridPosIterator<'a> {
+        position: Position,
+        grid: &'a Grid,
     }
 
-    fn number() -> u8 {
-        8
+    impl<'a> Iterator for GridPosIterator<'a> {
+        type Item = (Position, u32);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let x_move = (self.position.0 + 1, self.position.1);
+            let y_move = (0, self.position.1 + 1);
+            if let Some(v) = self.grid.get(x_move) {
+                self.position = x_move;
+                Some((x_move, v))
+            } else if let Some(v) = self.grid.get(y_move) {
+                self.position = y_move;
+                Some((y_move, v))
+            } else {
+                None
+            }
+        }
     }
-}
 
-fn main() {
-    Day08::run();
+    #[derive(Debug)]
+    struct GridIterator<'a> {
+        position: Position,
+        direction: Position,
+        grid: &'a Grid,
+    }
+
+    impl<'a> Iterator for GridIterator<'a> {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.position.0 += self.direction.0;
+            self.position.1 += self.direction.1;
+
+            self.grid.get(self.position)
+        }
+    }
+
+    fn visible(grid: &Grid) -> usize {
+        grid.survey()
+            .visible
+            .iter()
+            .flatten()
+            .filter(|&&v| v)
+            .count()
+    }
+
+    #[test]
+    fn example() {
+        let day = Day08::parse_input(EXAMPLE);
+        assert_eq!(day.part1(), "21");
+        assert_eq!(day.part2(), "8");
+
+        let survey = day.grid.survey();
+        assert_eq!(survey.best(), ((2, 3), 8));
+        assert_eq!(survey.scores[1][2], 4);
+    }
+
+    #[test]
+    fn matches_the_reference_on_the_example() {
+        let grid = Grid::new(EXAMPLE);
+
+        assert_eq!(grid.positions().count(), 25);
+        assert_eq!(visible(&grid), grid.reference_visible());
+        assert_eq!(grid.survey().best().1, grid.reference_best_score());
+    }
+
+    proptest! {
+        #[test]
+        fn matches_the_reference(rows in (1..15usize, 1..15usize).prop_flat_map(|(w, h)| {
+            proptest::collection::vec(proptest::collection::vec(0..10u32, w), h)
+        })) {
+            let grid = Grid { map: rows };
+
+            prop_assert_eq!(visible(&grid), grid.reference_visible());
+            prop_assert_eq!(grid.survey().best().1, grid.reference_best_score());
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use util::viz::{Frame, Grid, Visualize};
 use util::*;
 
 #[derive(Debug, Copy, Clone)]
@@ -8,6 +9,39 @@ enum Direction {
     R,
     L,
     D,
+    UL,
+    UR,
+    DL,
+    DR,
+}
+
+impl Direction {
+    fn new(input: &str) -> Self {
+        match input {
+            "U" => Direction::U,
+            "R" => Direction::R,
+            "L" => Direction::L,
+            "D" => Direction::D,
+            "UL" => Direction::UL,
+            "UR" => Direction::UR,
+            "DL" => Direction::DL,
+            "DR" => Direction::DR,
+            _ => panic!("Invalid direction {input}"),
+        }
+    }
+
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::U => (0, -1),
+            Direction::R => (1, 0),
+            Direction::L => (-1, 0),
+            Direction::D => (0, 1),
+            Direction::UL => (-1, -1),
+            Direction::UR => (1, -1),
+            Direction::DL => (-1, 1),
+            Direction::DR => (1, 1),
+        }
+    }
 }
 
 struct Step {
@@ -17,55 +51,146 @@ struct Step {
 
 impl Step {
     fn new(input: &str) -> Self {
-        let mut s = input.split(' ');
-        let dir = s.next().unwrap();
-        let count = s.next().unwrap().parse().unwrap();
-
-        match dir {
-            "U" => Step {
-                direction: Direction::U,
-                count,
-            },
-            "R" => Step {
-                direction: Direction::R,
-                count,
-            },
-            "L" => Step {
-                direction: Direction::L,
-                count,
-            },
-            "D" => Step {
-                direction: Direction::D,
-                count,
-            },
-            _ => panic!("Invalid"),
+        let (dir, count) = input.split_once(' ').expect("Invalid step");
+
+        Step {
+            direction: Direction::new(dir),
+            count: count.parse().unwrap(),
         }
     }
 }
 
+struct Rope {
+    knots: Vec<Position>,
+    /// Every position each knot has been in, head first.
+    visited: Vec<HashSet<Position>>,
+}
+
+impl Rope {
+    fn new(length: usize) -> Self {
+        assert!(length > 1, "A rope needs at least two knots");
+
+        Self {
+            knots: vec![Position::new(); length],
+            visited: vec![HashSet::from([Position::new()]); length],
+        }
+    }
+
+    fn step(&mut self, d: Direction) {
+        self.knots[0].apply(d);
+
+        self.visited[0].insert(self.knots[0]);
+
+        for i in 1..self.knots.len() {
+            let previous = self.knots[i - 1];
+            while self.knots[i].follow(previous) {
+                self.visited[i].insert(self.knots[i]);
+            }
+        }
+    }
+
+    fn tail_visited(&self) -> &HashSet<Position> {
+        self.visited.last().unwrap()
+    }
+
+    /// The smallest and largest corner of the area every knot has been in.
+    fn bounds(&self) -> (Position, Position) {
+        let all = || self.visited.iter().flatten();
+        let min = Position {
+            x: all().map(|p| p.x).min().unwrap(),
+            y: all().map(|p| p.y).min().unwrap(),
+        };
+        let max = Position {
+            x: all().map(|p| p.x).max().unwrap(),
+            y: all().map(|p| p.y).max().unwrap(),
+        };
+
+        (min, max)
+    }
+
+    /// Draws the tail's trail as `#` with the start as `s`, and the knots
+    /// on top of it when `knots` is set, over the area from `min` to `max`.
+    fn render(&self, (min, max): (Position, Position), knots: bool) -> Grid<char> {
+        let cell = |p: Position| ((p.x - min.x) as usize, (p.y - min.y) as usize);
+        let mut grid = Grid::new(
+            (max.x - min.x + 1) as usize,
+            (max.y - min.y + 1) as usize,
+            '.',
+        );
+
+        for &p in self.tail_visited() {
+            let (x, y) = cell(p);
+            grid.set(x, y, '#');
+        }
+        let (x, y) = cell(Position::new());
+        grid.set(x, y, 's');
+
+        if knots {
+            for (i, &p) in self.knots.iter().enumerate().rev() {
+                let c = match i {
+                    0 => 'H',
+                    _ => char::from_digit(i as u32, 36).unwrap_or('*'),
+                };
+                let (x, y) = cell(p);
+                grid.set(x, y, c);
+            }
+        }
+
+        grid
+    }
+}
+
 struct Day09 {
     steps: Vec<Step>,
+    /// The number of knots in the visualized rope.
+    knots: usize,
 }
 
 impl Day09 {
-    fn apply_steps<const ROPE_SIZE: usize>(&self) -> usize {
-        assert!(ROPE_SIZE > 1);
+    /// Every single move of the head, in order.
+    fn moves(&self) -> impl Iterator<Item = Direction> + '_ {
+        self.steps
+            .iter()
+            .flat_map(|s| std::iter::repeat_n(s.direction, s.count))
+    }
 
-        let mut positions = [Position::new(); ROPE_SIZE];
-        let mut unique_pos = HashSet::new();
-        for s in &self.steps {
-            for _ in 0..s.count {
-                positions[0].apply(s.direction);
+    fn simulate(&self, length: usize) -> Rope {
+        let mut rope = Rope::new(length);
+        for d in self.moves() {
+            rope.step(d);
+        }
 
-                for i in 1..positions.len() {
-                    positions[i].follow(positions[i - 1]);
-                }
+        rope
+    }
+}
+
+impl Visualize for Day09 {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let bounds = self.simulate(self.knots).bounds();
+        let mut rope = Rope::new(self.knots);
+        let first = Frame::Text(rope.render(bounds, true));
 
-                unique_pos.insert(*positions.last().unwrap());
+        let mut moves = self.moves();
+        let mut done = false;
+        let rest = std::iter::from_fn(move || {
+            if done {
+                return None;
             }
-        }
 
-        unique_pos.len()
+            match moves.next() {
+                Some(d) => {
+                    rope.step(d);
+                    Some(Frame::Text(rope.render(bounds, true)))
+                }
+                // The trail on its own, like the puzzle's diagram.
+                None => {
+                    done = true;
+                    Some(Frame::Text(rope.render(bounds, false)))
+                }
+            }
+        });
+
+        Box::new(std::iter::once(first).chain(rest))
     }
 }
 
@@ -81,22 +206,27 @@ impl Position {
     }
 
     fn apply(&mut self, d: Direction) {
-        match d {
-            Direction::U => self.y -= 1,
-            Direction::D => self.y += 1,
-            Direction::L => self.x -= 1,
-            Direction::R => self.x += 1,
-        };
+        let (dx, dy) = d.delta();
+        self.x += dx;
+        self.y += dy;
     }
 
-    fn follow(&mut self, other: Position) {
-        let dx = other.x - self.x;
-        let dy = other.y - self.y;
+    fn is_touching(self, other: Position) -> bool {
+        (other.x - self.x).abs() <= 1 && (other.y - self.y).abs() <= 1
+    }
 
-        if dx.abs() == 2 || dy.abs() == 2 {
-            self.x += dx.signum();
-            self.y += dy.signum();
+    /// Moves one step towards `other` if it is not touching, straight when
+    /// in the same row or column and diagonally otherwise. Returns whether it
+    /// moved, so a knot far behind can keep following until it touches.
+    fn follow(&mut self, other: Position) -> bool {
+        if self.is_touching(other) {
+            return false;
         }
+
+        self.x += (other.x - self.x).signum();
+        self.y += (other.y - self.y).signum();
+
+        true
     }
 }
 
@@ -104,22 +234,134 @@ impl Day for Day09 {
     fn parse_input(input: &str) -> Self {
         let steps = input.lines().map(Step::new).collect();
 
-        Self { steps }
+        Self { steps, knots: 10 }
+    }
+
+    /// `--knots N` sets the length of the visualized rope.
+    fn configure(&mut self, args: &[String]) -> Result<(), String> {
+        if let Some(knots) = args::parse(args, "--knots")? {
+            if knots < 2 {
+                return Err("--knots needs at least 2 knots".to_owned());
+            }
+            self.knots = knots;
+        }
+
+        Ok(())
     }
 
     fn part1(&self) -> String {
-        self.apply_steps::<2>().to_string()
+        self.simulate(2).tail_visited().len().to_string()
     }
 
     fn part2(&self) -> String {
-        self.apply_steps::<10>().to_string()
+        self.simulate(10).tail_visited().len().to_string()
     }
 
     fn number() -> u8 {
         9
     }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
 }
 
 fn main() {
     Day09::run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const DIRECTIONS: [Direction; 8] = [
+        Direction::U,
+        Direction::R,
+        Direction::L,
+        Direction::D,
+        Direction::UL,
+        Direction::UR,
+        Direction::DL,
+        Direction::DR,
+    ];
+
+    const SMALL: &str = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+    const LARGE: &str = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20";
+
+    #[test]
+    fn examples() {
+        let small = Day09::parse_input(SMALL);
+        assert_eq!(small.part1(), "13");
+        assert_eq!(small.part2(), "1");
+
+        let large = Day09::parse_input(LARGE);
+        assert_eq!(large.part1(), "88");
+        assert_eq!(large.part2(), "36");
+    }
+
+    #[test]
+    fn knots_option() {
+        let args = |s: &str| -> Vec<String> { s.split_whitespace().map(str::to_owned).collect() };
+        let mut day = Day09::parse_input(SMALL);
+
+        assert_eq!(day.configure(&args("day09 --knots 3")), Ok(()));
+        assert_eq!(day.knots, 3);
+        for knots in ["0", "1"] {
+            assert_eq!(
+                day.configure(&args(&format!("day09 --knots {knots}"))),
+                Err("--knots needs at least 2 knots".to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn diagonal_steps() {
+        let day = Day09::parse_input("UR 3\nDL 1\nDR 2");
+        let rope = day.simulate(2);
+
+        assert_eq!(rope.knots[0], Position { x: 4, y: 0 });
+        assert_eq!(rope.knots[1], Position { x: 3, y: -1 });
+        assert_eq!(rope.tail_visited().len(), 4);
+    }
+
+    #[test]
+    fn follows_from_far_away() {
+        for target in [
+            Position { x: 7, y: 3 },
+            Position { x: -5, y: 0 },
+            Position { x: 0, y: 6 },
+            Position { x: -6, y: -6 },
+        ] {
+            let mut knot = Position::new();
+            let mut moves = 0;
+            while knot.follow(target) {
+                moves += 1;
+            }
+
+            assert!(knot.is_touching(target), "{knot:?} behind {target:?}");
+            assert_eq!(moves, target.x.abs().max(target.y.abs()) - 1);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn knots_stay_adjacent(
+            length in 2..12usize,
+            steps in proptest::collection::vec((0..8usize, 1..10usize), 0..50),
+        ) {
+            let mut rope = Rope::new(length);
+
+            for (d, count) in steps {
+                for _ in 0..count {
+                    rope.step(DIRECTIONS[d]);
+
+                    for pair in rope.knots.windows(2) {
+                        prop_assert!(pair[1].is_touching(pair[0]), "{:?}", rope.knots);
+                    }
+                    prop_assert!(rope.tail_visited().contains(rope.knots.last().unwrap()));
+                }
+            }
+        }
+    }
+}
@@ -1,82 +1,396 @@
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+use util::ocr;
+use util::viz::{Frame, Grid, Visualize};
 use util::*;
 
-enum Instruction {
-    Add(isize),
-    Noop,
+/// How an instruction is written, how long it takes to run and what it does.
+#[derive(Debug)]
+struct Opcode {
+    mnemonic: &'static str,
+    cycles: usize,
+    /// Whether the instruction takes a number after its mnemonic.
+    operand: bool,
+    /// The value of X once the last cycle of the instruction ends, from X and
+    /// the operand.
+    execute: fn(isize, isize) -> isize,
+}
+
+const NOOP: Opcode = Opcode {
+    mnemonic: "noop",
+    cycles: 1,
+    operand: false,
+    execute: |x, _| x,
+};
+
+const ADDX: Opcode = Opcode {
+    mnemonic: "addx",
+    cycles: 2,
+    operand: true,
+    execute: |x, v| x + v,
+};
+
+const OPCODES: &[&Opcode] = &[&NOOP, &ADDX];
+
+#[derive(Debug, Copy, Clone)]
+struct Instruction {
+    opcode: &'static Opcode,
+    /// The number after the mnemonic, or 0 for opcodes without one.
+    operand: isize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProgramErrorKind {
+    UnknownMnemonic(String),
+    MissingOperand(&'static str),
+    UnexpectedOperand(&'static str),
+    InvalidOperand(String),
+}
+
+/// An instruction that could not be parsed, with the line number it is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProgramError {
+    line: usize,
+    kind: ProgramErrorKind,
+}
+
+impl std::fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ProgramErrorKind::UnknownMnemonic(m) => write!(f, "unknown instruction `{m}`"),
+            ProgramErrorKind::MissingOperand(m) => write!(f, "`{m}` needs an operand"),
+            ProgramErrorKind::UnexpectedOperand(m) => write!(f, "`{m}` takes no operand"),
+            ProgramErrorKind::InvalidOperand(v) => write!(f, "invalid operand `{v}`"),
+        }
+    }
 }
 
+impl std::error::Error for ProgramError {}
+
 impl Instruction {
-    fn new(input: &str) -> Self {
-        let mut s = input.split(' ');
-        let ins = s.next().unwrap();
-        let v = s.next();
-        match (ins, v) {
-            ("noop", None) => Instruction::Noop,
-            ("addx", Some(v)) => Instruction::Add(v.parse().unwrap()),
-            _ => panic!("Invalid"),
+    fn new(input: &str) -> Result<Self, ProgramErrorKind> {
+        let mut s = input.split_whitespace();
+        let mnemonic = s.next().unwrap_or_default();
+        let opcode = OPCODES
+            .iter()
+            .find(|o| o.mnemonic == mnemonic)
+            .ok_or_else(|| ProgramErrorKind::UnknownMnemonic(mnemonic.to_owned()))?;
+
+        let operand = match (opcode.operand, s.next()) {
+            (true, Some(v)) => v
+                .parse()
+                .map_err(|_| ProgramErrorKind::InvalidOperand(v.to_owned()))?,
+            (true, None) => return Err(ProgramErrorKind::MissingOperand(opcode.mnemonic)),
+            (false, None) => 0,
+            (false, Some(_)) => return Err(ProgramErrorKind::UnexpectedOperand(opcode.mnemonic)),
+        };
+
+        match s.next() {
+            Some(_) => Err(ProgramErrorKind::UnexpectedOperand(opcode.mnemonic)),
+            None => Ok(Self { opcode, operand }),
+        }
+    }
+
+    fn cycles(&self) -> usize {
+        self.opcode.cycles
+    }
+
+    /// The value of X after the instruction has run.
+    fn execute(&self, x: isize) -> isize {
+        (self.opcode.execute)(x, self.operand)
+    }
+}
+
+impl std::fmt::Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.opcode.mnemonic)?;
+        if self.opcode.operand {
+            write!(f, " {}", self.operand)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses one instruction per line, skipping blank lines.
+fn parse_program(input: &str) -> Result<Vec<Instruction>, ProgramError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| Instruction::new(l).map_err(|kind| ProgramError { line: i + 1, kind }))
+        .collect()
+}
+
+/// Lists a program with the address, first cycle and value of X at the start
+/// of each instruction.
+fn disassemble(program: &[Instruction]) -> String {
+    let mut listing = format!("{:>5} {:>6} {:>4}  instruction\n", "addr", "cycle", "x");
+    let (mut cycle, mut x) = (1, 1);
+
+    for (pc, i) in program.iter().enumerate() {
+        listing.push_str(&format!("{pc:>5} {cycle:>6} {x:>4}  {i}\n"));
+
+        cycle += i.cycles();
+        x = i.execute(x);
+    }
+
+    listing
+}
+
+/// Writes a program that draws `image`, where `#` is a lit pixel, on a
+/// screen the size of the image. X can only change after an `addx`, which
+/// holds it for two cycles, so some images cannot be drawn.
+fn assemble(image: &Grid<char>) -> Result<Vec<Instruction>, String> {
+    let width = image.width();
+    let cycles = width * image.height();
+    let lit = |c: usize| *image.get(c % width, c / width).unwrap() == '#';
+    let fits =
+        |c: usize, x: isize| c >= cycles || lit(c) == ((x - (c % width) as isize).abs() <= 1);
+
+    // Whether the rest of the image from each cycle can be drawn with X
+    // starting at each value, working back from the end. X never needs to
+    // go further than one past the edges to keep a pixel dark.
+    let xs = || -2..=width as isize + 1;
+    let index = |x: isize| (x + 2) as usize;
+    let mut drawable = vec![vec![true; xs().count()]; cycles + 2];
+    for c in (0..cycles).rev() {
+        let any_next = drawable[c + 2].contains(&true);
+        for x in xs() {
+            drawable[c][index(x)] =
+                fits(c, x) && (drawable[c + 1][index(x)] || (fits(c + 1, x) && any_next));
+        }
+    }
+
+    let (mut c, mut x) = (0, 1);
+    let mut program = Vec::new();
+    while c < cycles {
+        if !drawable[c][index(x)] {
+            return Err(format!(
+                "the image cannot be drawn from pixel {} of row {} on",
+                c % width,
+                c / width
+            ));
+        }
+
+        if drawable[c + 1][index(x)] {
+            program.push(Instruction {
+                opcode: &NOOP,
+                operand: 0,
+            });
+            c += 1;
+        } else {
+            let target = xs().find(|&y| drawable[c + 2][index(y)]).unwrap();
+            program.push(Instruction {
+                opcode: &ADDX,
+                operand: target - x,
+            });
+            (c, x) = (c + 2, target);
+        }
+    }
+
+    Ok(program)
+}
+
+/// The cycles whose signal strength is recorded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Sampling {
+    first: isize,
+    every: isize,
+    /// How many signals to record, or all of them.
+    count: Option<usize>,
+}
+
+impl Sampling {
+    fn samples(&self, cycle: isize, recorded: usize) -> bool {
+        let due = cycle == self.first
+            || (cycle > self.first && self.every > 0 && (cycle - self.first) % self.every == 0);
+
+        due && self.count.is_none_or(|n| recorded < n)
+    }
+}
+
+/// The screen size and sampling schedule of the machine.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Config {
+    width: usize,
+    height: usize,
+    sampling: Sampling,
+}
+
+impl Default for Config {
+    /// The machine from the puzzle.
+    fn default() -> Self {
+        Self {
+            width: 40,
+            height: 6,
+            sampling: Sampling {
+                first: 20,
+                every: 40,
+                count: Some(6),
+            },
         }
     }
 }
 
+impl Config {
+    /// Reads `--screen <width>x<height>` and
+    /// `--sample <first>[,<every>[,<count>]]` from the command line, where a
+    /// count of 0 records every signal.
+    fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = Config::default();
+
+        if let Some(screen) = args::value(args, "--screen")? {
+            let size = screen
+                .split_once('x')
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                .filter(|&(w, h)| w > 0 && h > 0);
+            (config.width, config.height) =
+                size.ok_or_else(|| format!("invalid screen size `{screen}`"))?;
+        }
+
+        if let Some(sample) = args::value(args, "--sample")? {
+            let numbers: Vec<isize> = sample
+                .split(',')
+                .map(|n| n.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("invalid sampling `{sample}`"))?;
+
+            config.sampling = match numbers[..] {
+                [first] => Sampling {
+                    first,
+                    every: 0,
+                    count: None,
+                },
+                [first, every] => Sampling {
+                    first,
+                    every,
+                    count: None,
+                },
+                [first, every, count] => Sampling {
+                    first,
+                    every,
+                    count: (count > 0).then_some(count as usize),
+                },
+                _ => return Err(format!("invalid sampling `{sample}`")),
+            };
+        }
+
+        Ok(config)
+    }
+}
+
 struct Day10 {
     instructions: Vec<Instruction>,
+    config: Config,
 }
 
-struct Computer {
+/// What happened during one cycle.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Cycle {
+    number: isize,
+    /// The value of X during the cycle, before any instruction finishes.
+    x: isize,
+    pixel: char,
+}
+
+struct Computer<'a> {
+    program: &'a [Instruction],
     cycle: isize,
     x: isize,
+    /// The index of the instruction being run.
+    pc: usize,
+    /// How many cycles have been spent on the current instruction.
+    busy: usize,
     signals: Vec<isize>,
-    image: [char; 40 * 6],
+    config: Config,
+    image: Vec<char>,
 }
 
-impl Computer {
-    fn new() -> Self {
+impl<'a> Computer<'a> {
+    fn new(program: &'a [Instruction], config: Config) -> Self {
         Self {
+            program,
+            config,
             cycle: 0,
             x: 1,
+            pc: 0,
+            busy: 0,
             signals: Vec::new(),
-            image: [' '; 40 * 6],
+            image: vec![' '; config.width * config.height],
         }
     }
 
-    fn check_cycle(&mut self) {
+    fn check_cycle(&mut self) -> Cycle {
         self.cycle += 1;
-        if self.cycle == 20 || self.cycle % 40 == 20 {
+        if self.config.sampling.samples(self.cycle, self.signals.len()) {
             self.signals.push(self.cycle * self.x);
         }
 
-        let cycle = (self.cycle - 1) % 40;
+        let cycle = (self.cycle - 1) % self.config.width as isize;
         let xs = [self.x - 1, self.x, self.x + 1];
+        let pixel = if xs.contains(&cycle) { '#' } else { '.' };
 
         if let Some(p) = self.image.get_mut((self.cycle - 1) as usize) {
-            if xs.iter().any(|x| *x == cycle) {
-                *p = '#'
-            } else {
-                *p = '.'
-            }
+            *p = pixel;
         };
+
+        Cycle {
+            number: self.cycle,
+            x: self.x,
+            pixel,
+        }
     }
 
-    fn run(&mut self, instructions: &[Instruction]) {
-        for i in instructions {
-            match i {
-                Instruction::Add(v) => {
-                    self.check_cycle();
-                    self.check_cycle();
-                    self.x += v
-                }
-                Instruction::Noop => {
-                    self.check_cycle();
-                }
-            }
+    /// Runs a single cycle, or returns `None` once the program has finished.
+    fn step(&mut self) -> Option<Cycle> {
+        let instruction = self.program.get(self.pc)?;
+        let cycle = self.check_cycle();
+
+        self.busy += 1;
+        if self.busy == instruction.cycles() {
+            self.x = instruction.execute(self.x);
+            self.pc += 1;
+            self.busy = 0;
         }
+
+        Some(cycle)
+    }
+
+    /// Runs the whole program, calling `on_cycle` after every cycle.
+    fn run_with(&mut self, mut on_cycle: impl FnMut(&Cycle)) {
+        while let Some(cycle) = self.step() {
+            on_cycle(&cycle);
+        }
+    }
+
+    fn run(&mut self) {
+        self.run_with(|_| {});
+    }
+
+    /// The screen with only the first `drawn` pixels shown.
+    fn partial_screen(&self, drawn: usize) -> Grid<char> {
+        let width = self.config.width;
+        let mut grid = Grid::new(width, self.config.height, ' ');
+        for (i, &c) in self.image.iter().take(drawn).enumerate() {
+            grid.set(i % width, i / width, c);
+        }
+
+        grid
+    }
+
+    fn screen(&self) -> Grid<char> {
+        self.partial_screen(self.image.len())
     }
 }
 
-impl std::fmt::Display for Computer {
+impl std::fmt::Display for Computer<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f)?;
-        for row in self.image.chunks_exact(40) {
+        for row in self.image.chunks_exact(self.config.width) {
             for c in row {
                 write!(f, "{c}")?;
             }
@@ -86,32 +400,676 @@ impl std::fmt::Display for Computer {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Breakpoint {
+    Cycle(isize),
+    /// Stops on any cycle during which X has this value.
+    X(isize),
+}
+
+impl Breakpoint {
+    fn matches(self, cycle: &Cycle) -> bool {
+        match self {
+            Breakpoint::Cycle(n) => cycle.number == n,
+            Breakpoint::X(x) => cycle.x == x,
+        }
+    }
+}
+
+impl std::fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Breakpoint::Cycle(n) => write!(f, "cycle {n}"),
+            Breakpoint::X(x) => write!(f, "x {x}"),
+        }
+    }
+}
+
+/// Runs a program cycle by cycle, keeping a trace of every cycle so far.
+struct Debugger<'a> {
+    computer: Computer<'a>,
+    breakpoints: Vec<Breakpoint>,
+    trace: Vec<Cycle>,
+}
+
+impl<'a> Debugger<'a> {
+    fn new(program: &'a [Instruction], config: Config) -> Self {
+        Self {
+            computer: Computer::new(program, config),
+            breakpoints: Vec::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    fn step(&mut self) -> Option<Cycle> {
+        let cycle = self.computer.step()?;
+        self.trace.push(cycle);
+
+        Some(cycle)
+    }
+
+    /// Runs until a cycle hits a breakpoint, returning it, or until the
+    /// program finishes.
+    fn resume(&mut self) -> Option<(Cycle, Breakpoint)> {
+        while let Some(cycle) = self.step() {
+            if let Some(&b) = self.breakpoints.iter().find(|b| b.matches(&cycle)) {
+                return Some((cycle, b));
+            }
+        }
+
+        None
+    }
+
+    fn write_trace(&self, out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(out, "cycle,x,pixel")?;
+        for c in &self.trace {
+            writeln!(out, "{},{},{}", c.number, c.x, c.pixel)?;
+        }
+
+        Ok(())
+    }
+
+    fn save_trace(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_trace(&mut file)?;
+        file.flush()
+    }
+
+    fn print_state(&self) {
+        let c = &self.computer;
+        print!(
+            "Cycle {} X {}",
+            c.cycle.bright_yellow(),
+            c.x.bright_yellow()
+        );
+        match c.program.get(c.pc) {
+            Some(i) => println!(" next {} {i}", c.pc.bright_blue()),
+            None => println!(" {}", "halted".bright_red()),
+        }
+    }
+
+    /// Reads commands from stdin until the user quits.
+    fn interact(&mut self) -> std::io::Result<()> {
+        const HELP: &str = "Commands:
+    s [n]          step n cycles, 1 by default
+    c              continue to the next breakpoint
+    b cycle <n>    break on cycle n
+    b x <n>        break on cycles where X is n
+    d              delete all breakpoints
+    i              show the current state and breakpoints
+    screen         show the screen
+    trace <path>   write the trace so far as CSV
+    q              quit";
+
+        println!("{HELP}");
+        self.print_state();
+
+        let stdin = std::io::stdin();
+        let mut line = String::new();
+        loop {
+            print!("{} ", "(day10)".bright_green());
+            std::io::stdout().flush()?;
+
+            line.clear();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let number = |s: Option<&&str>| s.and_then(|s| s.parse::<isize>().ok());
+
+            match words[..] {
+                [] => continue,
+                ["s" | "step", ..] => {
+                    for _ in 0..number(words.get(1)).unwrap_or(1) {
+                        if let Some(c) = self.step() {
+                            println!("Cycle {} X {} pixel {}", c.number, c.x, c.pixel);
+                        }
+                    }
+                }
+                ["c" | "continue"] => match self.resume() {
+                    Some((c, b)) => println!("Hit breakpoint {b} on cycle {}", c.number),
+                    None => println!("Program finished"),
+                },
+                ["b" | "break", kind @ ("cycle" | "x"), n] => match n.parse() {
+                    Ok(n) => {
+                        let b = match kind {
+                            "cycle" => Breakpoint::Cycle(n),
+                            _ => Breakpoint::X(n),
+                        };
+                        println!("Breaking on {b}");
+                        self.breakpoints.push(b);
+                    }
+                    Err(_) => println!("Invalid number {n}"),
+                },
+                ["d" | "delete"] => self.breakpoints.clear(),
+                ["i" | "info"] => {
+                    for b in &self.breakpoints {
+                        println!("Breakpoint on {b}");
+                    }
+                }
+                ["screen"] => print!("{}", self.computer.screen()),
+                ["trace", path] => match self.save_trace(Path::new(path)) {
+                    Ok(()) => println!("Wrote {} cycles to {path}", self.trace.len()),
+                    Err(e) => println!("Could not write trace: {}", e.bright_red()),
+                },
+                ["q" | "quit"] => return Ok(()),
+                _ => println!("{HELP}"),
+            }
+
+            self.print_state();
+        }
+    }
+}
+
+impl Visualize for Day10 {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut computer = Computer::new(&self.instructions, self.config);
+
+        // The screen as it is drawn, one pixel per cycle.
+        Box::new(std::iter::from_fn(move || {
+            let cycle = computer.step()?;
+
+            Some(Frame::Text(computer.partial_screen(cycle.number as usize)))
+        }))
+    }
+}
+
 impl Day for Day10 {
     fn parse_input(input: &str) -> Self {
-        let instructions = input.lines().map(Instruction::new).collect();
+        let instructions = parse_program(input).unwrap_or_else(|e| panic!("Invalid program: {e}"));
+
+        Self {
+            instructions,
+            config: Config::default(),
+        }
+    }
+
+    fn configure(&mut self, args: &[String]) -> Result<(), String> {
+        self.config = Config::from_args(args)?;
 
-        Self { instructions }
+        Ok(())
     }
 
     fn part1(&self) -> String {
-        let mut computer = Computer::new();
-        computer.run(&self.instructions);
+        let mut computer = Computer::new(&self.instructions, self.config);
+        computer.run();
 
-        computer.signals.iter().take(6).sum::<isize>().to_string()
+        computer.signals.iter().sum::<isize>().to_string()
     }
 
     fn part2(&self) -> String {
-        let mut computer = Computer::new();
-        computer.run(&self.instructions);
+        let mut computer = Computer::new(&self.instructions, self.config);
+        computer.run();
 
-        computer.to_string()
+        // Falls back to the image itself when it is not made of letters.
+        ocr::read(&computer.screen()).unwrap_or_else(|_| computer.to_string())
     }
 
     fn number() -> u8 {
         10
     }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
+}
+
+/// Reads the program at `path`, or the puzzle input without one.
+fn load_program(path: Option<&str>) -> Result<Vec<Instruction>, String> {
+    let input = match path {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?,
+        None => input::Inputs::from_env()
+            .get(Day10::year(), Day10::number())
+            .map_err(|e| e.to_string())?,
+    };
+
+    parse_program(&input).map_err(|e| e.to_string())
+}
+
+/// Runs the tool picked on the command line instead of the puzzle:
+/// * `--debug [program]` steps through a program interactively.
+/// * `--trace <csv> [--program <program>]` writes a program's trace.
+/// * `--disassemble [program]` lists a program.
+/// * `--assemble <image>` writes a program that draws an image.
+///
+/// Programs default to the puzzle input, and run on the machine configured
+/// by `--screen` and `--sample`.
+fn tool(args: &[String]) -> Option<Result<(), String>> {
+    ["--assemble", "--disassemble", "--debug", "--trace"]
+        .into_iter()
+        .any(|name| args::flag(args, name))
+        .then(|| run_tool(args))
+}
+
+fn run_tool(args: &[String]) -> Result<(), String> {
+    if let Some(path) = args::value(args, "--assemble")? {
+        let image = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+        for i in assemble(&Grid::from_lines(&image))? {
+            println!("{i}");
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = args::optional(args, "--disassemble") {
+        print!("{}", disassemble(&load_program(path)?));
+        return Ok(());
+    }
+
+    let path = match args::optional(args, "--debug").flatten() {
+        Some(path) => Some(path),
+        None => args::value(args, "--program")?,
+    };
+    let program = load_program(path)?;
+    let mut debugger = Debugger::new(&program, Config::from_args(args)?);
+
+    match args::value(args, "--trace")? {
+        Some(path) => {
+            while debugger.step().is_some() {}
+            debugger
+                .save_trace(Path::new(path))
+                .map_err(|e| e.to_string())?;
+            println!("Wrote {} cycles to {path}", debugger.trace.len());
+        }
+        None => debugger.interact().map_err(|e| e.to_string())?,
+    }
+
+    Ok(())
 }
 
 fn main() {
-    Day10::run();
+    let args: Vec<String> = std::env::args().collect();
+
+    match tool(&args) {
+        Some(Err(e)) => {
+            eprintln!("{}", e.bright_red());
+            std::process::exit(1);
+        }
+        Some(Ok(())) => {}
+        None => Day10::run(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+";
+
+    /// The letters used in `screen`, in the 4x6 font.
+    const FONT: &[(char, [&str; 6])] = &[
+        ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+        ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+        ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+        ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+        ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+        ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+    ];
+
+    /// A screen showing `word`, with a blank column after each letter.
+    fn screen(word: &str) -> Grid<char> {
+        let rows: Vec<String> = (0..6)
+            .map(|y| {
+                word.chars()
+                    .map(|c| FONT.iter().find(|&&(l, _)| l == c).unwrap().1[y])
+                    .map(|row| format!("{row}."))
+                    .collect()
+            })
+            .collect();
+
+        Grid::from_lines(&rows.join("\n"))
+    }
+
+    fn run(program: &[Instruction]) -> Grid<char> {
+        let mut computer = Computer::new(program, Config::default());
+        computer.run();
+
+        computer.screen()
+    }
+
+    fn error(input: &str) -> ProgramError {
+        parse_program(input).unwrap_err()
+    }
+
+    fn config(args: &str) -> Result<Config, String> {
+        let args: Vec<String> = args.split_whitespace().map(str::to_owned).collect();
+        Config::from_args(&args)
+    }
+
+    #[test]
+    fn parses_programs() {
+        let program = parse_program("noop\n\naddx 3\n  addx -5  \n").unwrap();
+        let listing: Vec<String> = program.iter().map(Instruction::to_string).collect();
+
+        assert_eq!(listing, ["noop", "addx 3", "addx -5"]);
+        assert_eq!(program.iter().map(Instruction::cycles).sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let unknown = error("noop\naddx 3\n\nmulx 2\nnoop");
+        assert_eq!(
+            unknown,
+            ProgramError {
+                line: 4,
+                kind: ProgramErrorKind::UnknownMnemonic("mulx".to_owned())
+            }
+        );
+        assert_eq!(unknown.to_string(), "line 4: unknown instruction `mulx`");
+
+        assert_eq!(
+            error("noop\naddx").kind,
+            ProgramErrorKind::MissingOperand("addx")
+        );
+        assert_eq!(error("noop\naddx").line, 2);
+        assert_eq!(
+            error("noop 1").kind,
+            ProgramErrorKind::UnexpectedOperand("noop")
+        );
+        assert_eq!(
+            error("addx 1 2").kind,
+            ProgramErrorKind::UnexpectedOperand("addx")
+        );
+        assert_eq!(
+            error("noop\nnoop\naddx one").kind,
+            ProgramErrorKind::InvalidOperand("one".to_owned())
+        );
+        assert_eq!(error("noop\nnoop\naddx one").line, 3);
+    }
+
+    #[test]
+    fn samples_every_signal() {
+        let program = parse_program(&"noop\n".repeat(300)).unwrap();
+        let mut computer = Computer::new(&program, config("day10 --sample 20,40,0").unwrap());
+        computer.run();
+
+        assert_eq!(computer.signals, [20, 60, 100, 140, 180, 220, 260, 300]);
+
+        let mut computer = Computer::new(&program, Config::default());
+        computer.run();
+
+        assert_eq!(computer.signals, [20, 60, 100, 140, 180, 220]);
+    }
+
+    #[test]
+    fn resizes_the_screen() {
+        let program = parse_program(&"noop\n".repeat(300)).unwrap();
+        let mut computer = Computer::new(&program, config("day10 --screen 40x12").unwrap());
+        computer.run();
+
+        let screen = computer.screen();
+        assert_eq!((screen.width(), screen.height()), (40, 12));
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert_eq!(config("day10"), Ok(Config::default()));
+        assert_eq!(
+            config("day10 --screen 0x6"),
+            Err("invalid screen size `0x6`".to_owned())
+        );
+        assert_eq!(
+            Config::from_args(&["day10".to_owned(), "--sample".to_owned(), String::new()]),
+            Err("invalid sampling ``".to_owned())
+        );
+        assert_eq!(
+            config("day10 --sample 1,2,3,4"),
+            Err("invalid sampling `1,2,3,4`".to_owned())
+        );
+        assert_eq!(
+            config("day10 --sample"),
+            Err("--sample needs a value".to_owned())
+        );
+    }
+
+    #[test]
+    fn example() {
+        let d = Day10::parse_input(EXAMPLE);
+        assert_eq!(d.part1(), "13140");
+        assert_eq!(
+            run(&d.instructions),
+            Grid::from_lines(
+                "##..##..##..##..##..##..##..##..##..##..\n\
+                 ###...###...###...###...###...###...###.\n\
+                 ####....####....####....####....####....\n\
+                 #####.....#####.....#####.....#####.....\n\
+                 ######......######......######......####\n\
+                 #######.......#######.......#######....."
+            )
+        );
+    }
+
+    #[test]
+    fn stops_at_breakpoints() {
+        let program = parse_program(EXAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program, Config::default());
+        debugger.breakpoints.push(Breakpoint::Cycle(20));
+
+        let (cycle, breakpoint) = debugger.resume().unwrap();
+        assert_eq!(breakpoint, Breakpoint::Cycle(20));
+        assert_eq!((cycle.number, cycle.x), (20, 21));
+        assert_eq!(debugger.trace.len(), 20);
+
+        assert_eq!(debugger.resume(), None);
+        assert_eq!(debugger.trace.len(), 240);
+    }
+
+    #[test]
+    fn stops_on_every_cycle_with_x() {
+        let program = parse_program("noop\naddx 3\naddx -5\nnoop").unwrap();
+        let mut debugger = Debugger::new(&program, Config::default());
+        debugger.breakpoints.push(Breakpoint::X(4));
+
+        let mut stops = Vec::new();
+        while let Some((cycle, _)) = debugger.resume() {
+            stops.push((cycle.number, cycle.x));
+        }
+
+        assert_eq!(stops, [(4, 4), (5, 4)]);
+    }
+
+    #[test]
+    fn writes_traces() {
+        let program = parse_program(EXAMPLE).unwrap();
+        let mut debugger = Debugger::new(&program, Config::default());
+        while debugger.step().is_some() {}
+
+        let mut csv = Vec::new();
+        debugger.write_trace(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[..5],
+            ["cycle,x,pixel", "1,1,#", "2,1,#", "3,16,.", "4,16,."]
+        );
+        assert_eq!(lines.len(), 241);
+        assert_eq!(lines[240], "240,17,.");
+    }
+
+    #[test]
+    fn disassembles() {
+        let program = parse_program("noop\naddx 3\naddx -5\nnoop").unwrap();
+
+        assert_eq!(
+            disassemble(&program),
+            " addr  cycle    x  instruction\n\
+             \x20   0      1    1  noop\n\
+             \x20   1      2    1  addx 3\n\
+             \x20   2      4    4  addx -5\n\
+             \x20   3      6   -1  noop\n"
+        );
+    }
+
+    #[test]
+    fn assembles_images_that_read_back() {
+        let image = screen("EFZBRPFE");
+        let program = assemble(&image).unwrap();
+
+        assert_eq!(run(&program), image);
+        assert_eq!(ocr::read(&run(&program)), Ok("EFZBRPFE".to_owned()));
+
+        // The listing is a program in its own right.
+        let listing: String = program.iter().map(|i| format!("{i}\n")).collect();
+        let reparsed = parse_program(&listing).unwrap();
+        assert_eq!(run(&reparsed), image);
+    }
+
+    #[test]
+    fn refuses_images_it_cannot_draw() {
+        // The sprite is three pixels wide, so it cannot light a pixel between
+        // two dark ones away from the edge.
+        let mut image = Grid::new(40, 6, '.');
+        for x in (0..40).step_by(3) {
+            image.set(x, 0, '#');
+        }
+
+        assert!(assemble(&image).is_err());
+    }
 }
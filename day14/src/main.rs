@@ -1,128 +1,375 @@
-use std::collections::HashSet;
-
+use util::viz::{Frame, Grid, Visualize};
 use util::*;
 
 type Point = (usize, usize);
 
-struct Day14 {
-    walls: Vec<(Point, Point)>,
+const SOURCE: Point = (500, 0);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Mode {
+    /// Sand falling past the lowest rock is lost.
+    Abyss,
+    /// There is an endless floor two below the lowest rock.
+    Floor,
 }
 
-impl Day14 {
-    fn get_map(&self) -> HashSet<Point> {
-        let mut map = HashSet::new();
-        for &((mut x0, mut y0), (mut x1, mut y1)) in &self.walls {
-            if y0 > y1 {
-                std::mem::swap(&mut y0, &mut y1);
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+struct Cave {
+    cells: Vec<Cell>,
+    width: usize,
+    /// The x coordinate of the first column of `cells`.
+    min_x: usize,
+    bottom: usize,
+    mode: Mode,
+    /// The path the last grain fell along, which the next grain follows
+    /// until it reaches the last cell that is still open.
+    path: Vec<Point>,
+    sand: usize,
+}
+
+impl Cave {
+    /// A cave with the given rock, wide enough for any sand pile that fits
+    /// above the floor.
+    fn new(walls: &[(Point, Point)], mode: Mode) -> Self {
+        let points = || walls.iter().flat_map(|&(a, b)| [a, b]);
+        let bottom = points().map(|(_, y)| y).max().unwrap_or(0);
+        let spread = bottom + 2;
+
+        let min_x = points()
+            .map(|(x, _)| x)
+            .chain([SOURCE.0.saturating_sub(spread)])
+            .min()
+            .unwrap()
+            .saturating_sub(1);
+        let max_x = points()
+            .map(|(x, _)| x)
+            .chain([SOURCE.0 + spread])
+            .max()
+            .unwrap()
+            + 1;
+        let width = max_x - min_x + 1;
+
+        let mut cave = Self {
+            cells: vec![Cell::Air; width * (bottom + 3)],
+            width,
+            min_x,
+            bottom,
+            mode,
+            path: Vec::new(),
+            sand: 0,
+        };
+
+        for &((x0, y0), (x1, y1)) in walls {
+            for y in y0.min(y1)..=y0.max(y1) {
+                for x in x0.min(x1)..=x0.max(x1) {
+                    cave.set((x, y), Cell::Rock);
+                }
+            }
+        }
+
+        cave
+    }
+
+    fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    fn index(&self, (x, y): Point) -> usize {
+        y * self.width + x - self.min_x
+    }
+
+    fn get(&self, p: Point) -> Cell {
+        if self.mode == Mode::Floor && p.1 == self.bottom + 2 {
+            Cell::Rock
+        } else {
+            self.cells[self.index(p)]
+        }
+    }
+
+    fn set(&mut self, p: Point, cell: Cell) {
+        let i = self.index(p);
+        self.cells[i] = cell;
+    }
+
+    /// Drops one grain of sand, returning whether it came to rest. Sand stops
+    /// coming once it falls into the abyss or the source is blocked.
+    fn drop_sand(&mut self) -> bool {
+        if self.path.is_empty() {
+            if self.get(SOURCE) != Cell::Air {
+                return false;
             }
-            if x0 > x1 {
-                std::mem::swap(&mut x0, &mut x1);
+            self.path.push(SOURCE);
+        }
+
+        while let Some(&(x, y)) = self.path.last() {
+            if self.mode == Mode::Abyss && y >= self.bottom {
+                return false;
             }
-            for y in y0..=y1 {
-                for x in x0..=x1 {
-                    map.insert((x, y));
+
+            match [x, x - 1, x + 1]
+                .map(|x| (x, y + 1))
+                .into_iter()
+                .find(|&p| self.get(p) == Cell::Air)
+            {
+                Some(p) => self.path.push(p),
+                None => {
+                    self.set((x, y), Cell::Sand);
+                    self.path.pop();
+                    self.sand += 1;
+                    return true;
                 }
             }
         }
 
-        map
+        unreachable!("The path always starts at the source")
+    }
+
+    fn fill(mut self) -> Self {
+        while self.drop_sand() {}
+        self
+    }
+
+    /// Draws the cave with rock as `#`, sand as `o` and the source as `+`,
+    /// wide enough for all the rock and sand.
+    fn render(&self) -> Grid<char> {
+        let used = |x: usize| {
+            x == SOURCE.0 || (0..=self.bottom + 1).any(|y| self.get((x, y)) != Cell::Air)
+        };
+        let columns = self.min_x..self.min_x + self.width;
+        let min_x = columns.clone().find(|&x| used(x)).unwrap() - 1;
+        let max_x = columns.rev().find(|&x| used(x)).unwrap() + 1;
+        let height = match self.mode {
+            Mode::Abyss => self.bottom + 1,
+            Mode::Floor => self.height(),
+        };
+
+        let mut grid = Grid::new(max_x - min_x + 1, height, '.');
+        for y in 0..height {
+            for x in min_x..=max_x {
+                let c = match self.get((x, y)) {
+                    Cell::Rock => '#',
+                    Cell::Sand => 'o',
+                    Cell::Air if (x, y) == SOURCE => '+',
+                    Cell::Air => '.',
+                };
+                grid.set(x - min_x, y, c);
+            }
+        }
+
+        grid
     }
 }
 
-fn parse_point(input: &str) -> Point {
-    input
-        .split_once(',')
-        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-        .unwrap()
+struct Day14 {
+    walls: Vec<(Point, Point)>,
+    /// The cave to visualize, with part 2's floor or part 1's abyss.
+    mode: Mode,
 }
 
-impl Day for Day14 {
-    fn parse_input(input: &str) -> Self {
-        let walls = input
-            .lines()
-            .flat_map(|l| {
-                l.split(" -> ")
-                    .collect::<Vec<_>>()
-                    .windows(2)
-                    .map(|s| (parse_point(s[0]), parse_point(s[1])))
-                    .collect::<Vec<_>>()
-            })
-            .collect();
+impl Visualize for Day14 {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut cave = Cave::new(&self.walls, self.mode);
+        let mut done = false;
+
+        Box::new(std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
 
-        Self { walls }
+            let frame = Frame::Text(cave.render());
+            done = !cave.drop_sand();
+
+            Some(frame)
+        }))
     }
+}
 
-    fn part1(&self) -> String {
-        let map = self.get_map();
-        let bottom = *map.iter().map(|(_, y)| y).max().unwrap();
-        let mut sands = HashSet::new();
-
-        'outer: loop {
-            let mut sand = (500usize, 0);
-            'falling: loop {
-                let mut settled = true;
-                for new_x in [sand.0, sand.0 - 1, sand.0 + 1] {
-                    let new_sand = (new_x, sand.1 + 1);
-
-                    if !map.contains(&new_sand) && !sands.contains(&new_sand) {
-                        sand = new_sand;
-                        settled = false;
-                        break;
-                    }
-                }
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WallErrorKind {
+    /// A point that is not two comma separated numbers.
+    InvalidPoint(String),
+    NegativeCoordinate(String),
+    /// A segment that is neither horizontal nor vertical.
+    Diagonal(String),
+}
 
-                if settled {
-                    sands.insert(sand);
-                    break 'falling;
-                } else if sand.1 >= bottom {
-                    break 'outer;
-                }
-            }
-        }
+/// An invalid rock path, with the line number it is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WallError {
+    line: usize,
+    kind: WallErrorKind,
+}
 
-        sands.len().to_string()
+impl std::fmt::Display for WallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            WallErrorKind::InvalidPoint(p) => write!(f, "invalid point `{p}`"),
+            WallErrorKind::NegativeCoordinate(p) => write!(f, "negative coordinate in `{p}`"),
+            WallErrorKind::Diagonal(s) => write!(f, "segment `{s}` is not horizontal or vertical"),
+        }
     }
+}
 
-    fn part2(&self) -> String {
-        let map = self.get_map();
-        let bottom = *map.iter().map(|(_, y)| y).max().unwrap();
-        let mut sands = HashSet::new();
-
-        'outer: loop {
-            let mut sand = (500usize, 0);
-            'falling: loop {
-                let mut settled = true;
-                for new_x in [sand.0, sand.0 - 1, sand.0 + 1] {
-                    let new_sand = (new_x, sand.1 + 1);
-
-                    if !map.contains(&new_sand)
-                        && !sands.contains(&new_sand)
-                        && new_sand.1 < bottom + 2
-                    {
-                        sand = new_sand;
-                        settled = false;
-                        break;
-                    }
-                }
+impl std::error::Error for WallError {}
 
-                if settled {
-                    sands.insert(sand);
-                    break 'falling;
-                }
+fn parse_point(input: &str) -> Result<Point, WallErrorKind> {
+    let input = input.trim();
+    let invalid = || WallErrorKind::InvalidPoint(input.to_owned());
+    let (x, y) = input.split_once(',').ok_or_else(invalid)?;
+
+    let coordinate = |c: &str| {
+        let c = c.trim();
+        match c.parse::<usize>() {
+            Ok(v) => Ok(v),
+            Err(_) if c.parse::<i64>().is_ok() => {
+                Err(WallErrorKind::NegativeCoordinate(input.to_owned()))
             }
-            if sand == (500, 0) {
-                break 'outer;
+            Err(_) => Err(invalid()),
+        }
+    };
+
+    Ok((coordinate(x)?, coordinate(y)?))
+}
+
+/// Parses one rock path into its segments, checking each is axis aligned.
+fn parse_path(l: &str) -> Result<Vec<(Point, Point)>, WallErrorKind> {
+    let points = l
+        .split("->")
+        .map(parse_point)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let [p] = points[..] {
+        return Ok(vec![(p, p)]);
+    }
+
+    points
+        .windows(2)
+        .map(|s| {
+            let ((x0, y0), (x1, y1)) = (s[0], s[1]);
+            if x0 == x1 || y0 == y1 {
+                Ok((s[0], s[1]))
+            } else {
+                Err(WallErrorKind::Diagonal(format!("{x0},{y0} -> {x1},{y1}")))
             }
+        })
+        .collect()
+}
+
+fn parse_walls(input: &str) -> Result<Vec<(Point, Point)>, WallError> {
+    let mut walls = Vec::new();
+
+    for (i, l) in input.lines().enumerate() {
+        if l.trim().is_empty() {
+            continue;
+        }
+
+        let path = parse_path(l).map_err(|kind| WallError { line: i + 1, kind })?;
+        walls.extend(path);
+    }
+
+    Ok(walls)
+}
+
+impl Day for Day14 {
+    fn parse_input(input: &str) -> Self {
+        let walls = parse_walls(input).unwrap_or_else(|e| panic!("Invalid cave: {e}"));
+
+        Self {
+            walls,
+            mode: Mode::Abyss,
+        }
+    }
+
+    /// `--floor` visualizes part 2's cave.
+    fn configure(&mut self, args: &[String]) -> Result<(), String> {
+        if args::flag(args, "--floor") {
+            self.mode = Mode::Floor;
         }
 
-        sands.len().to_string()
+        Ok(())
+    }
+
+    fn part1(&self) -> String {
+        Cave::new(&self.walls, Mode::Abyss).fill().sand.to_string()
+    }
+
+    fn part2(&self) -> String {
+        Cave::new(&self.walls, Mode::Floor).fill().sand.to_string()
     }
 
     fn number() -> u8 {
         14
     }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
 }
 
 fn main() {
     Day14::run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9
+";
+
+    fn error(input: &str) -> WallError {
+        parse_walls(input).unwrap_err()
+    }
+
+    #[test]
+    fn example() {
+        let d = Day14::parse_input(EXAMPLE);
+        assert_eq!(d.part1(), "24");
+        assert_eq!(d.part2(), "93");
+    }
+
+    #[test]
+    fn renders_the_floor() {
+        let cave = Cave::new(&[((500, 1), (500, 1))], Mode::Floor).fill();
+
+        assert_eq!(cave.sand, 8);
+        assert_eq!(
+            cave.render(),
+            Grid::from_lines("...o...\n..o#o..\n.ooooo.\n#######")
+        );
+    }
+
+    #[test]
+    fn single_point_paths() {
+        assert_eq!(parse_path("500,2"), Ok(vec![((500, 2), (500, 2))]));
+        assert_eq!(parse_walls("\n500,2\n"), Ok(vec![((500, 2), (500, 2))]));
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let e = error("498,4 -> 498,6\n498,4 -> 499,6");
+        assert_eq!(e.line, 2);
+        assert_eq!(e.kind, WallErrorKind::Diagonal("498,4 -> 499,6".to_owned()));
+        assert_eq!(
+            e.to_string(),
+            "line 2: segment `498,4 -> 499,6` is not horizontal or vertical"
+        );
+
+        let e = error("498,4 -> 498,6\n\n1,-1 -> 1,4");
+        assert_eq!(e.line, 3);
+        assert_eq!(e.kind, WallErrorKind::NegativeCoordinate("1,-1".to_owned()));
+
+        let e = error("1;2");
+        assert_eq!(e.line, 1);
+        assert_eq!(e.kind, WallErrorKind::InvalidPoint("1;2".to_owned()));
+        assert_eq!(e.to_string(), "line 1: invalid point `1;2`");
+    }
+}
@@ -1,18 +1,333 @@
+use std::collections::{HashMap, VecDeque};
+
 use util::*;
 
-struct Day22;
+type Point = (usize, usize);
+type Vector = [i32; 3];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Direction {
+    R,
+    D,
+    L,
+    U,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [Direction::R, Direction::D, Direction::L, Direction::U];
+
+    fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::R => (1, 0),
+            Direction::D => (0, 1),
+            Direction::L => (-1, 0),
+            Direction::U => (0, -1),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Move {
+    Forward(usize),
+    Left,
+    Right,
+}
+
+fn parse_path(input: &str) -> Vec<Move> {
+    let mut moves = Vec::new();
+    let mut count = None;
+
+    for c in input.trim().chars() {
+        match c {
+            '0'..='9' => {
+                count = Some(count.unwrap_or(0) * 10 + c.to_digit(10).unwrap() as usize);
+            }
+            'L' | 'R' => {
+                if let Some(n) = count.take() {
+                    moves.push(Move::Forward(n));
+                }
+                moves.push(if c == 'L' { Move::Left } else { Move::Right });
+            }
+            _ => panic!("Invalid path {c}"),
+        }
+    }
+
+    if let Some(n) = count {
+        moves.push(Move::Forward(n));
+    }
+
+    moves
+}
+
+fn neg(v: Vector) -> Vector {
+    [-v[0], -v[1], -v[2]]
+}
+
+/// Orientation of one face of the cube: its outward normal and the 3D
+/// vectors that the net's +x and +y directions point along on that face.
+#[derive(Debug, Copy, Clone)]
+struct Frame {
+    normal: Vector,
+    right: Vector,
+    down: Vector,
+}
+
+impl Frame {
+    fn vector(&self, d: Direction) -> Vector {
+        match d {
+            Direction::R => self.right,
+            Direction::D => self.down,
+            Direction::L => neg(self.right),
+            Direction::U => neg(self.down),
+        }
+    }
+
+    fn roll(&self, d: Direction) -> Self {
+        let Frame {
+            normal,
+            right,
+            down,
+        } = *self;
+
+        match d {
+            Direction::R => Frame {
+                normal: right,
+                right: neg(normal),
+                down,
+            },
+            Direction::L => Frame {
+                normal: neg(right),
+                right: normal,
+                down,
+            },
+            Direction::D => Frame {
+                normal: down,
+                right,
+                down: neg(normal),
+            },
+            Direction::U => Frame {
+                normal: neg(down),
+                right,
+                down: normal,
+            },
+        }
+    }
+}
+
+/// Edge stitching for the cube folded from the board's net, keyed by
+/// `(face, direction)` where `face` is the face's position in the net.
+struct Cube {
+    size: usize,
+    edges: HashMap<(Point, Direction), (Point, Direction)>,
+}
+
+impl Cube {
+    /// Folds the board into a cube.
+    ///
+    /// Panics if the board is not made of six equal square faces that fold
+    /// into a cube.
+    fn new(board: &Board) -> Self {
+        let tiles = board.map.iter().flatten().filter(|&&c| c != b' ').count();
+        let size = (1..=tiles)
+            .find(|s| s * s * 6 == tiles)
+            .expect("Board is not a cube net");
+
+        let faces: Vec<Point> = (0..board.map.len())
+            .step_by(size)
+            .flat_map(|y| {
+                (0..board.width)
+                    .step_by(size)
+                    .map(move |x| (x, y))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|&p| board.get(p) != b' ')
+            .map(|(x, y)| (x / size, y / size))
+            .collect();
+        assert!(faces.len() == 6, "Board is not a cube net");
+
+        let mut frames = HashMap::new();
+        let mut queue = VecDeque::new();
+        frames.insert(
+            faces[0],
+            Frame {
+                normal: [0, 0, 1],
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+            },
+        );
+        queue.push_back(faces[0]);
+
+        while let Some(face) = queue.pop_front() {
+            let frame = frames[&face];
+            for d in Direction::ALL {
+                let (dx, dy) = d.delta();
+                let next = (
+                    face.0.wrapping_add_signed(dx),
+                    face.1.wrapping_add_signed(dy),
+                );
+                if faces.contains(&next) && !frames.contains_key(&next) {
+                    frames.insert(next, frame.roll(d));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut edges = HashMap::new();
+        for (&face, frame) in &frames {
+            for d in Direction::ALL {
+                let normal = frame.vector(d);
+                let (&other, other_frame) = frames
+                    .iter()
+                    .find(|(_, f)| f.normal == normal)
+                    .expect("Net does not fold into a cube");
+                let entry = Direction::ALL
+                    .into_iter()
+                    .find(|&e| other_frame.vector(e) == frame.normal)
+                    .unwrap();
+
+                edges.insert((face, d), (other, entry.opposite()));
+            }
+        }
+
+        Self { size, edges }
+    }
+
+    fn wrap(&self, (x, y): Point, d: Direction) -> (Point, Direction) {
+        let n = self.size;
+        let face = (x / n, y / n);
+        let (lx, ly) = (x % n, y % n);
+
+        let along = match d {
+            Direction::R => ly,
+            Direction::D => n - 1 - lx,
+            Direction::L => n - 1 - ly,
+            Direction::U => lx,
+        };
+
+        let (other, new_d) = self.edges[&(face, d)];
+        let exit = new_d.opposite();
+
+        // Walking clockwise along the edge we leave by means walking
+        // anticlockwise along the edge we arrive through.
+        let along = n - 1 - along;
+        let (lx, ly) = match exit {
+            Direction::R => (n - 1, along),
+            Direction::D => (n - 1 - along, n - 1),
+            Direction::L => (0, n - 1 - along),
+            Direction::U => (along, 0),
+        };
+
+        ((other.0 * n + lx, other.1 * n + ly), new_d)
+    }
+}
+
+struct Board {
+    map: Vec<Vec<u8>>,
+    width: usize,
+}
+
+impl Board {
+    fn get(&self, (x, y): Point) -> u8 {
+        self.map
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(b' ')
+    }
+
+    fn step(&self, (x, y): Point, d: Direction) -> Option<Point> {
+        let (dx, dy) = d.delta();
+        let next = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+
+        (self.get(next) != b' ').then_some(next)
+    }
+
+    fn start(&self) -> Point {
+        let x = self.map[0].iter().position(|&c| c == b'.').unwrap();
+        (x, 0)
+    }
+
+    fn flat_wrap(&self, p: Point, d: Direction) -> (Point, Direction) {
+        let mut p = p;
+        while let Some(back) = self.step(p, d.opposite()) {
+            p = back;
+        }
+
+        (p, d)
+    }
+
+    fn walk(&self, moves: &[Move], wrap: impl Fn(Point, Direction) -> (Point, Direction)) -> usize {
+        let mut position = self.start();
+        let mut direction = Direction::R;
+
+        for m in moves {
+            match m {
+                Move::Left => direction = direction.turn_left(),
+                Move::Right => direction = direction.turn_right(),
+                Move::Forward(n) => {
+                    for _ in 0..*n {
+                        let (next, next_d) = match self.step(position, direction) {
+                            Some(next) => (next, direction),
+                            None => wrap(position, direction),
+                        };
+
+                        if self.get(next) == b'#' {
+                            break;
+                        }
+
+                        position = next;
+                        direction = next_d;
+                    }
+                }
+            }
+        }
+
+        1000 * (position.1 + 1) + 4 * (position.0 + 1) + direction as usize
+    }
+}
+
+struct Day22 {
+    board: Board,
+    moves: Vec<Move>,
+}
 
 impl Day for Day22 {
     fn parse_input(input: &str) -> Self {
-        todo!()
+        let (board, path) = input.split_once("\n\n").expect("Could not split");
+
+        let map: Vec<Vec<u8>> = board.lines().map(|l| l.bytes().collect()).collect();
+        let width = map.iter().map(Vec::len).max().unwrap();
+
+        Self {
+            board: Board { map, width },
+            moves: parse_path(path),
+        }
     }
 
     fn part1(&self) -> String {
-        todo!()
+        self.board
+            .walk(&self.moves, |p, d| self.board.flat_wrap(p, d))
+            .to_string()
     }
 
     fn part2(&self) -> String {
-        todo!()
+        let cube = Cube::new(&self.board);
+
+        self.board
+            .walk(&self.moves, |p, d| cube.wrap(p, d))
+            .to_string()
     }
 
     fn number() -> u8 {
@@ -24,3 +339,90 @@ fn main() {
     Day22::run();
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5
+";
+
+    /// Builds an open board from a net layout where every `#` is a face of
+    /// `size` by `size` tiles.
+    fn net(layout: &str, size: usize) -> Board {
+        let map: Vec<Vec<u8>> = layout
+            .lines()
+            .flat_map(|row| {
+                let line: Vec<u8> = row
+                    .bytes()
+                    .flat_map(|c| std::iter::repeat_n(if c == b'#' { b'.' } else { b' ' }, size))
+                    .collect();
+                std::iter::repeat_n(line, size)
+            })
+            .collect();
+        let width = map.iter().map(Vec::len).max().unwrap();
+
+        Board { map, width }
+    }
+
+    #[test]
+    fn example() {
+        let d = Day22::parse_input(EXAMPLE);
+        assert_eq!(d.part1(), "6032");
+        assert_eq!(d.part2(), "5031");
+    }
+
+    #[test]
+    fn wrapping_round_trips() {
+        let nets = [
+            "  #\n###\n  ##",
+            " #\n###\n #\n #",
+            " ##\n #\n##\n#",
+            "#\n##\n ##\n  #",
+            "##\n ###\n   #",
+            "  ###\n###",
+        ];
+
+        for layout in nets {
+            for size in [1, 3] {
+                let board = net(layout, size);
+                let cube = Cube::new(&board);
+
+                for y in 0..board.map.len() {
+                    for x in 0..board.width {
+                        let p = (x, y);
+                        if board.get(p) == b' ' {
+                            continue;
+                        }
+
+                        for d in Direction::ALL {
+                            if board.step(p, d).is_some() {
+                                continue;
+                            }
+
+                            let (q, e) = cube.wrap(p, d);
+                            assert_ne!(board.get(q), b' ', "{layout:?} {p:?} {d:?}");
+                            assert_eq!(
+                                cube.wrap(q, e.opposite()),
+                                (p, d.opposite()),
+                                "{layout:?} {p:?} {d:?}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
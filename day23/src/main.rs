@@ -1,26 +1,213 @@
+use std::collections::{HashMap, HashSet};
+
+use util::viz::{Frame, Grid, Visualize};
 use util::*;
 
-struct Day23;
+type Point = (i32, i32);
+
+#[derive(Debug, Copy, Clone)]
+enum Direction {
+    N,
+    S,
+    W,
+    E,
+}
+
+impl Direction {
+    const ORDER: [Direction; 4] = [Direction::N, Direction::S, Direction::W, Direction::E];
+
+    fn checks(self, (x, y): Point) -> [Point; 3] {
+        match self {
+            Direction::N => [(x - 1, y - 1), (x, y - 1), (x + 1, y - 1)],
+            Direction::S => [(x - 1, y + 1), (x, y + 1), (x + 1, y + 1)],
+            Direction::W => [(x - 1, y - 1), (x - 1, y), (x - 1, y + 1)],
+            Direction::E => [(x + 1, y - 1), (x + 1, y), (x + 1, y + 1)],
+        }
+    }
+
+    fn apply(self, (x, y): Point) -> Point {
+        match self {
+            Direction::N => (x, y - 1),
+            Direction::S => (x, y + 1),
+            Direction::W => (x - 1, y),
+            Direction::E => (x + 1, y),
+        }
+    }
+}
+
+fn neighbours((x, y): Point) -> [Point; 8] {
+    [
+        (x - 1, y - 1),
+        (x, y - 1),
+        (x + 1, y - 1),
+        (x - 1, y),
+        (x + 1, y),
+        (x - 1, y + 1),
+        (x, y + 1),
+        (x + 1, y + 1),
+    ]
+}
+
+#[derive(Clone)]
+struct Grove {
+    elves: HashSet<Point>,
+    round: usize,
+}
+
+impl Grove {
+    fn bounds(&self) -> (Point, Point) {
+        let min_x = self.elves.iter().map(|p| p.0).min().unwrap();
+        let max_x = self.elves.iter().map(|p| p.0).max().unwrap();
+        let min_y = self.elves.iter().map(|p| p.1).min().unwrap();
+        let max_y = self.elves.iter().map(|p| p.1).max().unwrap();
+
+        ((min_x, min_y), (max_x, max_y))
+    }
+
+    fn empty_ground(&self) -> usize {
+        let ((x0, y0), (x1, y1)) = self.bounds();
+        let area = (x1 - x0 + 1) as usize * (y1 - y0 + 1) as usize;
+
+        area - self.elves.len()
+    }
+
+    /// Runs one round and returns whether any elf moved.
+    fn step(&mut self) -> bool {
+        let mut proposals: HashMap<Point, Vec<Point>> = HashMap::new();
+
+        for &elf in &self.elves {
+            if neighbours(elf).iter().all(|p| !self.elves.contains(p)) {
+                continue;
+            }
+
+            let proposal = (0..4)
+                .map(|i| Direction::ORDER[(self.round + i) % 4])
+                .find(|d| d.checks(elf).iter().all(|p| !self.elves.contains(p)));
+
+            if let Some(d) = proposal {
+                proposals.entry(d.apply(elf)).or_default().push(elf);
+            }
+        }
+
+        let mut moved = false;
+        for (target, from) in proposals {
+            if let [elf] = from[..] {
+                self.elves.remove(&elf);
+                self.elves.insert(target);
+                moved = true;
+            }
+        }
+
+        self.round += 1;
+
+        moved
+    }
+
+    fn to_grid(&self) -> Grid<char> {
+        let ((x0, y0), (x1, y1)) = self.bounds();
+        let mut grid = Grid::new((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize, '.');
+
+        for &(x, y) in &self.elves {
+            grid.set((x - x0) as usize, (y - y0) as usize, '#');
+        }
+
+        grid
+    }
+}
+
+struct Day23 {
+    grove: Grove,
+}
+
+impl Day23 {
+    fn simulate(&self, mut until: impl FnMut(&Grove, bool) -> bool) -> Grove {
+        let mut grove = self.grove.clone();
+
+        loop {
+            let moved = grove.step();
+
+            if until(&grove, moved) {
+                break grove;
+            }
+        }
+    }
+}
+
+impl Visualize for Day23 {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        let mut grove = self.grove.clone();
+        let mut moved = true;
+
+        Box::new(std::iter::from_fn(move || {
+            if !moved {
+                return None;
+            }
+
+            let frame = Frame::Text(grove.to_grid());
+            moved = grove.step();
+
+            Some(frame)
+        }))
+    }
+}
 
 impl Day for Day23 {
     fn parse_input(input: &str) -> Self {
-        todo!()
+        let elves = input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, l)| {
+                l.char_indices()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(x, _)| (x as i32, y as i32))
+            })
+            .collect();
+
+        Self {
+            grove: Grove { elves, round: 0 },
+        }
     }
 
     fn part1(&self) -> String {
-        todo!()
+        self.simulate(|g, _| g.round == 10)
+            .empty_ground()
+            .to_string()
     }
 
     fn part2(&self) -> String {
-        todo!()
+        self.simulate(|_, moved| !moved).round.to_string()
     }
 
     fn number() -> u8 {
         23
     }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
 }
 
 fn main() {
     Day23::run();
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+";
+
+    #[test]
+    fn example() {
+        let d = Day23::parse_input(EXAMPLE);
+        assert_eq!(d.part1(), "110");
+        assert_eq!(d.part2(), "20");
+    }
+}
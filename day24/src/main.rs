@@ -1,26 +1,222 @@
+use pathfinding::directed::bfs::bfs;
+
+use util::viz::{Frame, Grid, Visualize};
 use util::*;
 
-struct Day24;
+type Point = (usize, usize);
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The basin with the blizzards stored at their starting positions. Every
+/// blizzard moves in a straight line and wraps around, so whether one covers
+/// a tile at a given minute only needs a lookup back along its row or column.
+struct Valley {
+    blizzards: Vec<Vec<u8>>,
+    width: usize,
+    height: usize,
+}
+
+impl Valley {
+    fn start(&self) -> Point {
+        (1, 0)
+    }
+
+    fn goal(&self) -> Point {
+        (self.width, self.height + 1)
+    }
+
+    /// The blizzard pattern repeats after this many minutes.
+    fn period(&self) -> usize {
+        self.width * self.height / gcd(self.width, self.height)
+    }
+
+    fn blizzards_at(&self, (x, y): Point, time: usize) -> impl Iterator<Item = u8> + '_ {
+        let (x, y) = (x - 1, y - 1);
+        let (w, h) = (self.width, self.height);
+
+        [
+            (b'>', ((x + w - time % w) % w, y)),
+            (b'<', ((x + time) % w, y)),
+            (b'v', (x, (y + h - time % h) % h)),
+            (b'^', (x, (y + time) % h)),
+        ]
+        .into_iter()
+        .filter(move |&(c, (x, y))| self.blizzards[y][x] == c)
+        .map(|(c, _)| c)
+    }
+
+    fn is_wall(&self, (x, y): Point) -> bool {
+        if (x, y) == self.start() || (x, y) == self.goal() {
+            return false;
+        }
+
+        x == 0 || y == 0 || x > self.width || y > self.height
+    }
+
+    fn is_open(&self, p: Point, time: usize) -> bool {
+        if self.is_wall(p) {
+            return false;
+        }
+
+        p == self.start() || p == self.goal() || self.blizzards_at(p, time).next().is_none()
+    }
+
+    fn successors(&self, ((x, y), time): (Point, usize)) -> Vec<(Point, usize)> {
+        let next = (time + 1) % self.period();
+
+        [
+            (x, y),
+            (x + 1, y),
+            (x, y + 1),
+            (x.wrapping_sub(1), y),
+            (x, y.wrapping_sub(1)),
+        ]
+        .into_iter()
+        .filter(|&p| p.0 <= self.width + 1 && p.1 <= self.height + 1)
+        .filter(|&p| self.is_open(p, next))
+        .map(|p| (p, next))
+        .collect()
+    }
+
+    /// Returns the positions visited on a shortest trip from `from` to `to`
+    /// leaving at minute `time`, including the starting position.
+    fn route(&self, from: Point, to: Point, time: usize) -> Vec<Point> {
+        bfs(
+            &(from, time % self.period()),
+            |&s| self.successors(s),
+            |&(p, _)| p == to,
+        )
+        .expect("No route through the valley")
+        .into_iter()
+        .map(|(p, _)| p)
+        .collect()
+    }
+
+    fn render(&self, time: usize, expedition: Point) -> Grid<char> {
+        let mut grid = Grid::new(self.width + 2, self.height + 2, '.');
+
+        for y in 0..=self.height + 1 {
+            for x in 0..=self.width + 1 {
+                let c = if (x, y) == expedition {
+                    'E'
+                } else if self.is_wall((x, y)) {
+                    '#'
+                } else if (x, y) == self.start() || (x, y) == self.goal() {
+                    '.'
+                } else {
+                    let here: Vec<_> = self.blizzards_at((x, y), time).collect();
+                    match here[..] {
+                        [] => '.',
+                        [c] => c as char,
+                        _ => char::from_digit(here.len() as u32, 10).unwrap(),
+                    }
+                };
+                grid.set(x, y, c);
+            }
+        }
+
+        grid
+    }
+}
+
+struct Day24 {
+    valley: Valley,
+}
+
+impl Day24 {
+    /// The three legs of the trip there, back and there again, each as the
+    /// minute it starts and the positions visited.
+    fn legs(&self) -> Vec<(usize, Vec<Point>)> {
+        let (start, goal) = (self.valley.start(), self.valley.goal());
+        let mut time = 0;
+
+        [(start, goal), (goal, start), (start, goal)]
+            .into_iter()
+            .map(|(from, to)| {
+                let route = self.valley.route(from, to, time);
+                let leg = (time, route);
+                time += leg.1.len() - 1;
+                leg
+            })
+            .collect()
+    }
+}
+
+impl Visualize for Day24 {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_> {
+        Box::new(self.legs().into_iter().flat_map(move |(time, route)| {
+            route
+                .into_iter()
+                .enumerate()
+                .map(move |(i, p)| Frame::Text(self.valley.render(time + i, p)))
+        }))
+    }
+}
 
 impl Day for Day24 {
     fn parse_input(input: &str) -> Self {
-        todo!()
+        let lines: Vec<_> = input.lines().collect();
+        let blizzards: Vec<Vec<u8>> = lines[1..lines.len() - 1]
+            .iter()
+            .map(|l| l.as_bytes()[1..l.len() - 1].to_vec())
+            .collect();
+
+        let valley = Valley {
+            width: blizzards[0].len(),
+            height: blizzards.len(),
+            blizzards,
+        };
+
+        Self { valley }
     }
 
     fn part1(&self) -> String {
-        todo!()
+        let (start, goal) = (self.valley.start(), self.valley.goal());
+
+        (self.valley.route(start, goal, 0).len() - 1).to_string()
     }
 
     fn part2(&self) -> String {
-        todo!()
+        let (time, route) = self.legs().pop().unwrap();
+
+        (time + route.len() - 1).to_string()
     }
 
     fn number() -> u8 {
         24
     }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        Some(self)
+    }
 }
 
 fn main() {
     Day24::run();
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+";
+
+    #[test]
+    fn example() {
+        let d = Day24::parse_input(EXAMPLE);
+        assert_eq!(d.part1(), "18");
+        assert_eq!(d.part2(), "54");
+    }
+}
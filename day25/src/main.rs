@@ -1,18 +1,26 @@
+use util::snafu::Snafu;
 use util::*;
 
-struct Day25;
+struct Day25 {
+    numbers: Vec<Snafu>,
+}
 
 impl Day for Day25 {
     fn parse_input(input: &str) -> Self {
-        todo!()
+        let numbers = input
+            .lines()
+            .map(|l| l.parse().expect("Not a SNAFU number"))
+            .collect();
+
+        Self { numbers }
     }
 
     fn part1(&self) -> String {
-        todo!()
+        self.numbers.iter().sum::<Snafu>().to_string()
     }
 
     fn part2(&self) -> String {
-        todo!()
+        "Start the blender!".to_string()
     }
 
     fn number() -> u8 {
@@ -23,4 +31,3 @@ impl Day for Day25 {
 fn main() {
     Day25::run();
 }
-
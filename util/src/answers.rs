@@ -0,0 +1,212 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::history::{escape, unescape};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+}
+
+impl Verdict {
+    fn name(self) -> &'static str {
+        match self {
+            Verdict::Right => "right",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "high",
+            Verdict::TooLow => "low",
+        }
+    }
+
+    fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "right" => Some(Verdict::Right),
+            "wrong" => Some(Verdict::Wrong),
+            "high" => Some(Verdict::TooHigh),
+            "low" => Some(Verdict::TooLow),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "right"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub part: u8,
+    pub verdict: Verdict,
+    pub answer: String,
+}
+
+/// Every answer submitted for one day and what the server said about it,
+/// stored one `part verdict answer` line per submission in `answers/<year>/NN`
+/// with the answer escaped as in the run history.
+pub struct Registry {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    pub fn load(dir: &Path, year: u16, day: u8) -> std::io::Result<Self> {
+        let path = dir.join(year.to_string()).join(format!("{day:02}"));
+
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(s) => s
+                .lines()
+                .filter_map(|l| {
+                    let mut parts = l.splitn(3, ' ');
+                    Some(Entry {
+                        part: parts.next()?.parse().ok()?,
+                        verdict: Verdict::from_name(parts.next()?)?,
+                        answer: unescape(parts.next()?),
+                    })
+                })
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self { path, entries })
+    }
+
+    pub fn entries(&self, part: u8) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(move |e| e.part == part)
+    }
+
+    pub fn right_answer(&self, part: u8) -> Option<&str> {
+        self.entries(part)
+            .find(|e| e.verdict == Verdict::Right)
+            .map(|e| e.answer.as_str())
+    }
+
+    /// Returns what is already known about `answer`, either because it was
+    /// submitted before or because it lies outside a "too high" or "too low"
+    /// bound from an earlier submission.
+    pub fn check(&self, part: u8, answer: &str) -> Option<Verdict> {
+        if let Some(right) = self.right_answer(part) {
+            return Some(if right == answer {
+                Verdict::Right
+            } else {
+                Verdict::Wrong
+            });
+        }
+
+        if let Some(e) = self.entries(part).find(|e| e.answer == answer) {
+            return Some(e.verdict);
+        }
+
+        let value: i128 = answer.parse().ok()?;
+        self.entries(part).find_map(|e| {
+            let bound: i128 = e.answer.parse().ok()?;
+            match e.verdict {
+                Verdict::TooHigh if value >= bound => Some(Verdict::TooHigh),
+                Verdict::TooLow if value <= bound => Some(Verdict::TooLow),
+                _ => None,
+            }
+        })
+    }
+
+    pub fn record(&mut self, part: u8, answer: &str, verdict: Verdict) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{part} {} {}", verdict.name(), escape(answer))?;
+
+        self.entries.push(Entry {
+            part,
+            verdict,
+            answer: answer.to_owned(),
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(entries: &[(u8, Verdict, &str)]) -> (tempfile::TempDir, Registry) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = Registry::load(dir.path(), 2022, 1).unwrap();
+        for &(part, verdict, answer) in entries {
+            registry.record(part, answer, verdict).unwrap();
+        }
+
+        (dir, registry)
+    }
+
+    #[test]
+    fn knows_submitted_answers() {
+        let (_dir, registry) = registry(&[(1, Verdict::Wrong, "abc"), (2, Verdict::TooLow, "7")]);
+
+        assert_eq!(registry.check(1, "abc"), Some(Verdict::Wrong));
+        assert_eq!(registry.check(1, "abd"), None);
+        assert_eq!(registry.check(2, "abc"), None);
+    }
+
+    #[test]
+    fn right_answer_rules_out_the_rest() {
+        let (_dir, registry) = registry(&[(1, Verdict::Right, "42")]);
+
+        assert_eq!(registry.right_answer(1), Some("42"));
+        assert_eq!(registry.check(1, "42"), Some(Verdict::Right));
+        assert_eq!(registry.check(1, "43"), Some(Verdict::Wrong));
+        assert_eq!(registry.check(2, "43"), None);
+    }
+
+    #[test]
+    fn bounds_rule_out_numbers_beyond_them() {
+        let (_dir, registry) =
+            registry(&[(1, Verdict::TooHigh, "100"), (1, Verdict::TooLow, "10")]);
+
+        assert_eq!(registry.check(1, "150"), Some(Verdict::TooHigh));
+        assert_eq!(registry.check(1, "100"), Some(Verdict::TooHigh));
+        assert_eq!(registry.check(1, "10"), Some(Verdict::TooLow));
+        assert_eq!(registry.check(1, "-5"), Some(Verdict::TooLow));
+        assert_eq!(registry.check(1, "99"), None);
+        assert_eq!(registry.check(1, "11"), None);
+        assert_eq!(registry.check(1, "PZULBAUA"), None);
+    }
+
+    #[test]
+    fn reloads_what_it_records() {
+        let image = "#..#\n####\n#..#";
+        let (dir, _) = registry(&[
+            (1, Verdict::TooHigh, "100"),
+            (2, Verdict::Wrong, image),
+            (2, Verdict::Right, "a b\\c"),
+        ]);
+
+        let registry = Registry::load(dir.path(), 2022, 1).unwrap();
+        let entries: Vec<_> = registry.entries(2).map(|e| e.answer.as_str()).collect();
+
+        assert_eq!(entries, [image, "a b\\c"]);
+        assert_eq!(registry.check(1, "100"), Some(Verdict::TooHigh));
+        assert_eq!(registry.check(2, image), Some(Verdict::Wrong));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("2022/01"))
+                .unwrap()
+                .lines()
+                .count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Options on the command line, where the value of an option is the argument
+//! after it unless that is another option.
+
+use std::str::FromStr;
+
+/// Whether the option `name` is given.
+pub fn flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|a| a == name)
+}
+
+/// The value of the option `name`, which is `None` when the option is not
+/// given and `Some(None)` when it is given without a value.
+pub fn optional<'a>(args: &'a [String], name: &str) -> Option<Option<&'a str>> {
+    let i = args.iter().position(|a| a == name)?;
+
+    Some(
+        args.get(i + 1)
+            .map(String::as_str)
+            .filter(|v| !v.starts_with("--")),
+    )
+}
+
+/// The value of the option `name`, which must have one when it is given.
+pub fn value<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+    optional(args, name)
+        .map(|v| v.ok_or_else(|| format!("{name} needs a value")))
+        .transpose()
+}
+
+/// The value of the option `name`, parsed.
+pub fn parse<T: FromStr>(args: &[String], name: &str) -> Result<Option<T>, String> {
+    value(args, name)?
+        .map(|v| v.parse().map_err(|_| format!("invalid {name} `{v}`")))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn flags() {
+        let a = args("day --last --viz");
+        assert!(flag(&a, "--last"));
+        assert!(!flag(&a, "--floor"));
+    }
+
+    #[test]
+    fn values() {
+        let a = args("day --viz out.gif --debug --fps 5 --step -1 --program");
+
+        assert_eq!(optional(&a, "--viz"), Some(Some("out.gif")));
+        assert_eq!(optional(&a, "--debug"), Some(None));
+        assert_eq!(optional(&a, "--program"), Some(None));
+        assert_eq!(optional(&a, "--scale"), None);
+
+        assert_eq!(value(&a, "--viz"), Ok(Some("out.gif")));
+        assert_eq!(value(&a, "--step"), Ok(Some("-1")));
+        assert_eq!(value(&a, "--scale"), Ok(None));
+        assert_eq!(
+            value(&a, "--debug"),
+            Err("--debug needs a value".to_owned())
+        );
+        assert_eq!(
+            value(&a, "--program"),
+            Err("--program needs a value".to_owned())
+        );
+    }
+
+    #[test]
+    fn parsed() {
+        let a = args("day --fps 5 --step -1 --viz out.gif --scale");
+
+        assert_eq!(parse(&a, "--fps"), Ok(Some(5u32)));
+        assert_eq!(parse(&a, "--step"), Ok(Some(-1i32)));
+        assert_eq!(parse::<u32>(&a, "--knots"), Ok(None));
+        assert_eq!(
+            parse::<u32>(&a, "--step"),
+            Err("invalid --step `-1`".to_owned())
+        );
+        assert_eq!(
+            parse::<u32>(&a, "--viz"),
+            Err("invalid --viz `out.gif`".to_owned())
+        );
+        assert_eq!(
+            parse::<u32>(&a, "--scale"),
+            Err("--scale needs a value".to_owned())
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The answers and timings of one `Day::run`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub parse: Duration,
+    pub part1: (String, Duration),
+    pub part2: (String, Duration),
+}
+
+impl Run {
+    pub fn new(parse: Duration, part1: (String, Duration), part2: (String, Duration)) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        Self {
+            timestamp,
+            parse,
+            part1,
+            part2,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1.1 + self.part2.1
+    }
+}
+
+/// Escapes newlines, tabs and backslashes so an answer fits on one line.
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+
+    out
+}
+
+/// Every recorded run of one day, stored one tab separated line per run in
+/// `history/<year>/NN` with timings in microseconds.
+pub struct History {
+    path: PathBuf,
+    pub runs: Vec<Run>,
+}
+
+impl History {
+    pub fn load(dir: &Path, year: u16, day: u8) -> std::io::Result<Self> {
+        let path = dir.join(year.to_string()).join(format!("{day:02}"));
+
+        let runs = match std::fs::read_to_string(&path) {
+            Ok(s) => s
+                .lines()
+                .filter_map(|l| {
+                    let mut fields = l.split('\t');
+                    let timestamp = fields.next()?.parse().ok()?;
+
+                    let mut micros = || Some(Duration::from_micros(fields.next()?.parse().ok()?));
+                    let (parse, part1_time, part2_time) = (micros()?, micros()?, micros()?);
+
+                    Some(Run {
+                        timestamp,
+                        parse,
+                        part1: (unescape(fields.next()?), part1_time),
+                        part2: (unescape(fields.next()?), part2_time),
+                    })
+                })
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self { path, runs })
+    }
+
+    pub fn last(&self) -> Option<&Run> {
+        self.runs.last()
+    }
+
+    pub fn record(&mut self, run: Run) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            run.timestamp,
+            run.parse.as_micros(),
+            run.part1.1.as_micros(),
+            run.part2.1.as_micros(),
+            escape(&run.part1.0),
+            escape(&run.part2.0),
+        )?;
+
+        self.runs.push(run);
+
+        Ok(())
+    }
+}
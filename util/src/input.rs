@@ -0,0 +1,198 @@
+use std::path::PathBuf;
+
+/// Loads puzzle inputs from the `input/<year>/` directories, downloading any that are
+/// missing or empty.
+///
+/// The endpoint and session token come from `AOC_ENDPOINT` and `AOC_SESSION`,
+/// so downloads can be pointed at a local server instead of the real site.
+pub struct Inputs {
+    dir: PathBuf,
+    endpoint: String,
+    session: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Io(std::io::Error),
+    MissingSession,
+    Http(String),
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Io(e) => write!(f, "{e}"),
+            InputError::MissingSession => {
+                write!(f, "input needs downloading but AOC_SESSION is not set")
+            }
+            InputError::Http(e) => write!(f, "could not download input: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<std::io::Error> for InputError {
+    fn from(e: std::io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+pub const DEFAULT_ENDPOINT: &str = "https://adventofcode.com";
+
+pub(crate) fn endpoint_from_env() -> String {
+    std::env::var("AOC_ENDPOINT").unwrap_or_else(|_| DEFAULT_ENDPOINT.to_owned())
+}
+
+pub(crate) fn session_from_env() -> Option<String> {
+    std::env::var("AOC_SESSION").ok().filter(|s| !s.is_empty())
+}
+
+impl Inputs {
+    pub fn new(dir: impl Into<PathBuf>, endpoint: &str, session: Option<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            session,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new("input", &endpoint_from_env(), session_from_env())
+    }
+
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("{day:02}"))
+    }
+
+    /// Whether the input for `day` still has to be downloaded.
+    pub fn is_missing(&self, year: u16, day: u8) -> bool {
+        std::fs::metadata(self.path(year, day)).map_or(true, |m| m.len() == 0)
+    }
+
+    pub fn url(&self, year: u16, day: u8) -> String {
+        format!("{}/{year}/day/{day}/input", self.endpoint)
+    }
+
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let session = self.session.as_ref().ok_or(InputError::MissingSession)?;
+
+        ureq::get(&self.url(year, day))
+            .set("Cookie", &format!("session={session}"))
+            .call()
+            .map_err(|e| InputError::Http(e.to_string()))?
+            .into_string()
+            .map_err(InputError::Io)
+    }
+
+    /// Returns the input for `day`, downloading and caching it first if the
+    /// cached file is missing or empty.
+    pub fn get(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let path = self.path(year, day);
+
+        if self.is_missing(year, day) {
+            let input = self.fetch(year, day)?;
+            if input.is_empty() {
+                return Err(InputError::Http(
+                    "server returned an empty input".to_owned(),
+                ));
+            }
+
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(&path, &input)?;
+
+            return Ok(input);
+        }
+
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::Server;
+
+    #[test]
+    fn downloads_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = Server::start(200, "1\n2\n3\n");
+        let inputs = Inputs::new(dir.path(), server.endpoint(), Some("abc".to_owned()));
+
+        assert!(inputs.is_missing(2022, 25));
+        assert_eq!(inputs.get(2022, 25).unwrap(), "1\n2\n3\n");
+        assert!(!inputs.is_missing(2022, 25));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("2022/25")).unwrap(),
+            "1\n2\n3\n"
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2022/day/25/input "));
+        assert!(requests[0].contains("session=abc"));
+
+        assert_eq!(inputs.get(2022, 25).unwrap(), "1\n2\n3\n");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn reads_cached_input_without_a_session() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("2022")).unwrap();
+        std::fs::write(dir.path().join("2022/01"), "cached").unwrap();
+
+        let server = Server::start(200, "downloaded");
+        let inputs = Inputs::new(dir.path(), server.endpoint(), None);
+
+        assert_eq!(inputs.get(2022, 1).unwrap(), "cached");
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn downloads_over_an_empty_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("2022")).unwrap();
+        std::fs::write(dir.path().join("2022/17"), "").unwrap();
+
+        let server = Server::start(200, "downloaded");
+        let inputs = Inputs::new(dir.path(), server.endpoint(), Some("abc".to_owned()));
+
+        assert!(inputs.is_missing(2022, 17));
+        assert_eq!(inputs.get(2022, 17).unwrap(), "downloaded");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn needs_a_session_to_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = Server::start(200, "downloaded");
+        let inputs = Inputs::new(dir.path(), server.endpoint(), None);
+
+        assert!(matches!(
+            inputs.get(2022, 25),
+            Err(InputError::MissingSession)
+        ));
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn rejects_an_empty_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = Server::start(200, "");
+        let inputs = Inputs::new(dir.path(), server.endpoint(), Some("abc".to_owned()));
+
+        assert!(matches!(inputs.get(2022, 25), Err(InputError::Http(_))));
+        assert!(!dir.path().join("2022/25").exists());
+    }
+
+    #[test]
+    fn reports_server_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = Server::start(500, "oops");
+        let inputs = Inputs::new(dir.path(), server.endpoint(), Some("abc".to_owned()));
+
+        assert!(matches!(inputs.get(2022, 25), Err(InputError::Http(_))));
+        assert!(inputs.is_missing(2022, 25));
+    }
+}
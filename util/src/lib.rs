@@ -1,4 +1,15 @@
-use std::path::PathBuf;
+pub mod answers;
+pub mod args;
+pub mod history;
+pub mod input;
+#[cfg(test)]
+mod mock;
+pub mod ocr;
+pub mod snafu;
+pub mod submit;
+pub mod viz;
+
+use std::path::Path;
 use std::time::Instant;
 
 use owo_colors::OwoColorize;
@@ -9,38 +20,111 @@ pub trait Day: Sized {
     fn part2(&self) -> String;
     fn number() -> u8;
 
+    fn year() -> u16 {
+        2022
+    }
+
+    fn visualization(&self) -> Option<&dyn viz::Visualize> {
+        None
+    }
+
+    /// Applies the day's own options from the command line. `parse_input`
+    /// always uses the defaults, and `run` calls this right after it.
+    fn configure(&mut self, _args: &[String]) -> Result<(), String> {
+        Ok(())
+    }
+
     fn run() {
-        println!("Day {}", Self::number().bright_red());
+        let args: Vec<String> = std::env::args().collect();
+        let (year, day) = (Self::year(), Self::number());
+        println!("Day {} of {}", day.bright_red(), year.bright_red());
 
         let now = Instant::now();
-        let p = PathBuf::from(format!("input/{:02}", Self::number()));
+        let inputs = input::Inputs::from_env();
+        let p = inputs.path(year, day);
 
+        if inputs.is_missing(year, day) {
+            println!("Downloading {}", inputs.url(year, day).bright_red());
+        }
         println!("Reading {}", p.display().bright_red());
 
-        let input = std::fs::read_to_string(p).unwrap();
+        let input = inputs
+            .get(year, day)
+            .unwrap_or_else(|e| panic!("Could not read input: {e}"));
 
-        let d = Self::parse_input(&input);
+        let mut d = Self::parse_input(&input);
+        if let Err(e) = d.configure(&args) {
+            eprintln!("{}", e.bright_red());
+            std::process::exit(1);
+        }
+        let parse_time = now.elapsed();
         println!(
             "Input parsing took {} ms",
-            now.elapsed().as_millis().bright_yellow()
+            parse_time.as_millis().bright_yellow()
         );
 
         let now = Instant::now();
         let part1 = d.part1();
+        let part1_time = now.elapsed();
 
         println!(
             "Part 1: {}, took {} ms",
             part1.bright_blue(),
-            now.elapsed().as_millis().bright_yellow()
+            part1_time.as_millis().bright_yellow()
         );
 
         let now = Instant::now();
         let part2 = d.part2();
+        let part2_time = now.elapsed();
 
         println!(
             "Part 2: {}, took {} ms",
             part2.bright_blue(),
-            now.elapsed().as_millis().bright_yellow()
+            part2_time.as_millis().bright_yellow()
+        );
+
+        let run = history::Run::new(
+            parse_time,
+            (part1.clone(), part1_time),
+            (part2.clone(), part2_time),
         );
+        if let Err(e) =
+            history::History::load(Path::new("history"), year, day).and_then(|mut h| h.record(run))
+        {
+            println!("Could not record run: {}", e.bright_red());
+        }
+
+        let submission = args::value(&args, "--submit").and_then(|part| {
+            part.map(|part| match part {
+                "1" => Ok((1, &part1)),
+                "2" => Ok((2, &part2)),
+                _ => Err(format!("part should be 1 or 2, not {part}")),
+            })
+            .transpose()
+        });
+
+        match submission {
+            Ok(Some((part, answer))) => {
+                match submit::Submitter::from_env().submit(year, day, part, answer) {
+                    Ok(response) => println!("Submitted part {part}: {}", response.bright_green()),
+                    Err(e) => println!("Did not submit part {part}: {}", e.bright_red()),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => println!("Did not submit: {}", e.bright_red()),
+        }
+
+        match viz::Options::from_args(&args) {
+            Ok(Some(options)) => match d.visualization() {
+                Some(v) => {
+                    if let Err(e) = options.render(v.frames()) {
+                        println!("Could not visualize: {}", e.bright_red());
+                    }
+                }
+                None => println!("Day {day} has no visualization"),
+            },
+            Ok(None) => {}
+            Err(e) => println!("Could not visualize: {}", e.bright_red()),
+        }
     }
 }
@@ -0,0 +1,264 @@
+use crate::viz::Grid;
+
+/// The 4x6 font most puzzles draw their letters in.
+const FONT_6: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', ".###\n..#.\n..#.\n..#.\n..#.\n.###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The larger 6x10 font.
+const FONT_10: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// No font is this many rows high, once blank rows are trimmed.
+    UnsupportedHeight(usize),
+    /// The glyph at this position, counting from 0, is not a known letter.
+    UnknownGlyph(usize),
+}
+
+impl std::fmt::Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::UnsupportedHeight(h) => write!(f, "no font is {h} pixels high"),
+            OcrError::UnknownGlyph(i) => write!(f, "unknown letter at position {i}"),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+fn is_lit(c: char) -> bool {
+    matches!(c, '#' | '█')
+}
+
+/// The lit pixels of each column, with blank columns as `None`.
+type Columns = Vec<Option<Vec<bool>>>;
+
+fn columns(rows: &[Vec<bool>]) -> Columns {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+    (0..width)
+        .map(|x| {
+            let column: Vec<bool> = rows
+                .iter()
+                .map(|r| r.get(x).copied().unwrap_or(false))
+                .collect();
+            column.contains(&true).then_some(column)
+        })
+        .collect()
+}
+
+/// Splits columns into glyphs at the blank columns between them.
+fn glyphs(columns: Columns) -> Vec<Vec<Vec<bool>>> {
+    columns
+        .split(Option::is_none)
+        .filter(|g| !g.is_empty())
+        .map(|g| g.iter().flatten().cloned().collect())
+        .collect()
+}
+
+fn font_glyphs(font: &[(char, &str)]) -> Vec<(char, Vec<Vec<bool>>)> {
+    font.iter()
+        .map(|&(c, s)| {
+            let rows: Vec<Vec<bool>> = s.lines().map(|l| l.chars().map(is_lit).collect()).collect();
+            let glyph = glyphs(columns(&rows)).concat();
+
+            (c, glyph)
+        })
+        .collect()
+}
+
+/// Reads the letters drawn with `#` or `█` in a grid, in either the 4x6 or
+/// the 6x10 font. Blank rows around the letters are ignored, and letters are
+/// told apart by the blank columns between them.
+pub fn read(grid: &Grid<char>) -> Result<String, OcrError> {
+    let rows: Vec<Vec<bool>> = grid
+        .rows()
+        .map(|r| r.iter().copied().map(is_lit).collect::<Vec<_>>())
+        .skip_while(|r| !r.contains(&true))
+        .collect();
+    let height = rows
+        .iter()
+        .rposition(|r| r.contains(&true))
+        .map_or(0, |i| i + 1);
+
+    let font = match height {
+        6 => font_glyphs(FONT_6),
+        10 => font_glyphs(FONT_10),
+        h => return Err(OcrError::UnsupportedHeight(h)),
+    };
+
+    glyphs(columns(&rows[..height]))
+        .iter()
+        .enumerate()
+        .map(|(i, glyph)| {
+            font.iter()
+                .find(|(_, g)| g == glyph)
+                .map(|&(c, _)| c)
+                .ok_or(OcrError::UnknownGlyph(i))
+        })
+        .collect()
+}
+
+/// Reads the letters drawn in text, as [`read`] does.
+pub fn read_str(s: &str) -> Result<String, OcrError> {
+    read(&Grid::from_lines(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws `letters` side by side with a blank column between them.
+    fn draw(font: &[(char, &str)], letters: &str) -> String {
+        let glyph = |c| font.iter().find(|&&(l, _)| l == c).unwrap().1;
+        let height = glyph(letters.chars().next().unwrap()).lines().count();
+
+        (0..height)
+            .map(|y| {
+                letters
+                    .chars()
+                    .map(|c| format!("{}.", glyph(c).lines().nth(y).unwrap()))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn reads_every_glyph() {
+        for font in [FONT_6, FONT_10] {
+            for &(c, _) in font {
+                assert_eq!(read_str(&draw(font, &c.to_string())), Ok(c.to_string()));
+            }
+
+            let all: String = font.iter().map(|&(c, _)| c).collect();
+            assert_eq!(read_str(&draw(font, &all)), Ok(all));
+        }
+    }
+
+    #[test]
+    fn glyphs_are_the_font_size() {
+        for (font, width, height) in [(FONT_6, 4, 6), (FONT_10, 6, 10)] {
+            for &(c, s) in font {
+                let rows: Vec<&str> = s.lines().collect();
+                assert_eq!(rows.len(), height, "{c}");
+                // Only Y is wider, as it is on the screen.
+                let expected = if c == 'Y' { 5 } else { width };
+                assert!(rows.iter().all(|r| r.len() == expected), "{c}");
+            }
+        }
+    }
+
+    #[test]
+    fn ignores_margins_and_reads_blocks() {
+        let screen = draw(FONT_6, "PZULBAUA").replace('#', "█");
+        let padded = format!("\n..\n{}\n", screen.replace('\n', "...\n"));
+
+        assert_eq!(read_str(&padded), Ok("PZULBAUA".to_owned()));
+    }
+
+    #[test]
+    fn unsupported_height() {
+        let short: String = draw(FONT_6, "HI")
+            .lines()
+            .take(5)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(read_str(&short), Err(OcrError::UnsupportedHeight(5)));
+        assert_eq!(read_str("....\n...."), Err(OcrError::UnsupportedHeight(0)));
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        let mut rows: Vec<String> = draw(FONT_6, "AB").lines().map(str::to_owned).collect();
+        for row in &mut rows {
+            row.push_str("##");
+        }
+
+        assert_eq!(read_str(&rows.join("\n")), Err(OcrError::UnknownGlyph(2)));
+        assert_eq!(
+            OcrError::UnknownGlyph(2).to_string(),
+            "unknown letter at position 2"
+        );
+    }
+}
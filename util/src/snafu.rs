@@ -0,0 +1,321 @@
+use std::ops::{Add, AddAssign};
+use std::str::FromStr;
+
+/// A number written in balanced base five, using the digits `=`, `-`, `0`,
+/// `1` and `2` for -2 to 2.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Snafu {
+    /// Least significant digit first, without trailing zeros.
+    digits: Vec<i8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSnafuError {
+    Empty,
+    InvalidDigit(char),
+}
+
+impl std::fmt::Display for ParseSnafuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseSnafuError::Empty => write!(f, "cannot parse SNAFU from empty string"),
+            ParseSnafuError::InvalidDigit(c) => write!(f, "invalid SNAFU digit {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseSnafuError {}
+
+/// The number does not fit in the target integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromSnafuError;
+
+impl std::fmt::Display for TryFromSnafuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SNAFU number out of range for integer type")
+    }
+}
+
+impl std::error::Error for TryFromSnafuError {}
+
+impl Snafu {
+    fn from_digits(mut digits: Vec<i8>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        Self { digits }
+    }
+}
+
+impl FromStr for Snafu {
+    type Err = ParseSnafuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseSnafuError::Empty);
+        }
+
+        let digits = s
+            .chars()
+            .rev()
+            .map(|c| match c {
+                '=' => Ok(-2),
+                '-' => Ok(-1),
+                '0' => Ok(0),
+                '1' => Ok(1),
+                '2' => Ok(2),
+                _ => Err(ParseSnafuError::InvalidDigit(c)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::from_digits(digits))
+    }
+}
+
+impl std::fmt::Display for Snafu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "0");
+        }
+
+        for d in self.digits.iter().rev() {
+            let c = match d {
+                -2 => '=',
+                -1 => '-',
+                0 => '0',
+                1 => '1',
+                2 => '2',
+                _ => unreachable!(),
+            };
+            write!(f, "{c}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<i128> for Snafu {
+    fn from(mut n: i128) -> Self {
+        let mut digits = Vec::new();
+        while n != 0 {
+            let mut d = n.rem_euclid(5);
+            n = n.div_euclid(5);
+            if d > 2 {
+                d -= 5;
+                n += 1;
+            }
+            digits.push(d as i8);
+        }
+
+        Self { digits }
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(n: i64) -> Self {
+        Self::from(n as i128)
+    }
+}
+
+impl TryFrom<&Snafu> for i128 {
+    type Error = TryFromSnafuError;
+
+    fn try_from(s: &Snafu) -> Result<Self, Self::Error> {
+        s.digits
+            .iter()
+            .rev()
+            .try_fold(0i128, |n, &d| {
+                let d = d as i128;
+                // Near the ends of the range `n * 5` can overflow even though
+                // `n * 5 + d` fits, so retry with one five moved into the digit.
+                n.checked_mul(5).and_then(|m| m.checked_add(d)).or_else(|| {
+                    (n - n.signum())
+                        .checked_mul(5)?
+                        .checked_add(d + 5 * n.signum())
+                })
+            })
+            .ok_or(TryFromSnafuError)
+    }
+}
+
+impl TryFrom<&Snafu> for i64 {
+    type Error = TryFromSnafuError;
+
+    fn try_from(s: &Snafu) -> Result<Self, Self::Error> {
+        i64::try_from(i128::try_from(s)?).map_err(|_| TryFromSnafuError)
+    }
+}
+
+impl Add<&Snafu> for &Snafu {
+    type Output = Snafu;
+
+    fn add(self, other: &Snafu) -> Snafu {
+        let len = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+
+        for i in 0..len {
+            let a = self.digits.get(i).copied().unwrap_or(0);
+            let b = other.digits.get(i).copied().unwrap_or(0);
+            let mut d = a + b + carry;
+
+            carry = 0;
+            if d > 2 {
+                d -= 5;
+                carry = 1;
+            } else if d < -2 {
+                d += 5;
+                carry = -1;
+            }
+            digits.push(d);
+        }
+        digits.push(carry);
+
+        Snafu::from_digits(digits)
+    }
+}
+
+impl Add for Snafu {
+    type Output = Snafu;
+
+    fn add(self, other: Snafu) -> Snafu {
+        &self + &other
+    }
+}
+
+impl AddAssign<&Snafu> for Snafu {
+    fn add_assign(&mut self, other: &Snafu) {
+        *self = &*self + other;
+    }
+}
+
+impl<'a> std::iter::Sum<&'a Snafu> for Snafu {
+    fn sum<I: Iterator<Item = &'a Snafu>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, s| &acc + s)
+    }
+}
+
+impl std::iter::Sum for Snafu {
+    fn sum<I: Iterator<Item = Snafu>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, s| acc + s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// The conversions listed in the puzzle.
+    const TABLE: &[(i128, &str)] = &[
+        (1, "1"),
+        (2, "2"),
+        (3, "1="),
+        (4, "1-"),
+        (5, "10"),
+        (7, "12"),
+        (8, "2="),
+        (9, "2-"),
+        (10, "20"),
+        (15, "1=0"),
+        (20, "1-0"),
+        (37, "122"),
+        (198, "2=0="),
+        (353, "1=-1="),
+        (906, "12111"),
+        (1747, "1=-0-2"),
+        (2022, "1=11-2"),
+        (4890, "2=-1=0"),
+        (12345, "1-0---0"),
+        (314159265, "1121-1110-1=0"),
+    ];
+
+    fn round_trip(n: i128) -> Result<i128, TryFromSnafuError> {
+        let s: Snafu = Snafu::from(n).to_string().parse().unwrap();
+        i128::try_from(&s)
+    }
+
+    #[test]
+    fn puzzle_table() {
+        for &(n, s) in TABLE {
+            assert_eq!(Snafu::from(n).to_string(), s);
+            assert_eq!(i128::try_from(&s.parse::<Snafu>().unwrap()), Ok(n));
+        }
+    }
+
+    #[test]
+    fn zero() {
+        assert_eq!(Snafu::default().to_string(), "0");
+        assert_eq!("000".parse::<Snafu>(), Ok(Snafu::default()));
+        assert_eq!(round_trip(0), Ok(0));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Snafu>(), Err(ParseSnafuError::Empty));
+        assert_eq!(
+            "1=3".parse::<Snafu>(),
+            Err(ParseSnafuError::InvalidDigit('3'))
+        );
+    }
+
+    #[test]
+    fn ends_of_range() {
+        for n in [i128::MIN, i128::MIN + 1, i128::MAX - 1, i128::MAX] {
+            assert_eq!(round_trip(n), Ok(n));
+        }
+        assert_eq!(round_trip(i64::MIN.into()), Ok(i64::MIN.into()));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let one = Snafu::from(1i128);
+        assert_eq!(
+            i128::try_from(&(&Snafu::from(i128::MAX) + &one)),
+            Err(TryFromSnafuError)
+        );
+        assert_eq!(
+            i128::try_from(&(&Snafu::from(i128::MIN) + &Snafu::from(-1i128))),
+            Err(TryFromSnafuError)
+        );
+
+        let big = Snafu::from(i64::MAX as i128 + 1);
+        assert_eq!(i64::try_from(&big), Err(TryFromSnafuError));
+        assert_eq!(i64::try_from(&Snafu::from(i64::MAX)), Ok(i64::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(n: i128) {
+            prop_assert_eq!(round_trip(n), Ok(n));
+        }
+
+        #[test]
+        fn round_trips_near_max(k in 0..1_000_000i128) {
+            prop_assert_eq!(round_trip(i128::MAX - k), Ok(i128::MAX - k));
+        }
+
+        #[test]
+        fn round_trips_near_min(k in 0..1_000_000i128) {
+            prop_assert_eq!(round_trip(i128::MIN + k), Ok(i128::MIN + k));
+        }
+
+        #[test]
+        fn round_trips_i64(n: i64) {
+            prop_assert_eq!(i64::try_from(&Snafu::from(n)), Ok(n));
+        }
+
+        #[test]
+        fn adds_digit_wise(a in i128::MIN / 2..i128::MAX / 2, b in i128::MIN / 2..i128::MAX / 2) {
+            prop_assert_eq!(&Snafu::from(a) + &Snafu::from(b), Snafu::from(a + b));
+        }
+
+        #[test]
+        fn sums(ns in proptest::collection::vec(any::<i64>(), 0..20)) {
+            let total: Snafu = ns.iter().map(|&n| Snafu::from(n)).sum();
+            let expected: i128 = ns.iter().map(|&n| n as i128).sum();
+            prop_assert_eq!(total, Snafu::from(expected));
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::answers::{Registry, Verdict};
+use crate::input::{endpoint_from_env, session_from_env};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// The answer was checked, possibly with a wait before the next attempt.
+    Checked(Verdict, Option<Duration>),
+    /// An answer was submitted too recently and this one was not checked.
+    TooRecent(Duration),
+    AlreadySolved,
+    Unrecognised(String),
+}
+
+impl std::fmt::Display for Response {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Response::Checked(v, None) => write!(f, "{v}"),
+            Response::Checked(v, Some(d)) => write!(f, "{v}, wait {}s", d.as_secs()),
+            Response::TooRecent(d) => write!(f, "too recent, wait {}s", d.as_secs()),
+            Response::AlreadySolved => write!(f, "already solved"),
+            Response::Unrecognised(s) => write!(f, "unrecognised response: {s}"),
+        }
+    }
+}
+
+fn parse_wait(body: &str) -> Option<Duration> {
+    if let Some(i) = body.find("You have ") {
+        let rest = &body[i + "You have ".len()..];
+        let rest = &rest[..rest.find(" left to wait")?];
+
+        return rest
+            .split_whitespace()
+            .map(|t| {
+                let (n, unit) = t.split_at(t.len() - 1);
+                let n: u64 = n.parse().ok()?;
+                match unit {
+                    "h" => Some(n * 3600),
+                    "m" => Some(n * 60),
+                    "s" => Some(n),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()
+            .map(Duration::from_secs);
+    }
+
+    let i = body.find("lease wait ")?;
+    let mut words = body[i + "lease wait ".len()..].split_whitespace();
+    let n = match words.next()? {
+        "one" => 1,
+        n => n.parse().ok()?,
+    };
+
+    words
+        .next()?
+        .starts_with("minute")
+        .then(|| Duration::from_secs(n * 60))
+}
+
+/// Reads the verdict out of the page the server returns for a submission.
+pub fn parse_response(body: &str) -> Response {
+    if body.contains("That's the right answer") {
+        Response::Checked(Verdict::Right, None)
+    } else if body.contains("That's not the right answer") {
+        let verdict = if body.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if body.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        };
+
+        Response::Checked(verdict, parse_wait(body))
+    } else if body.contains("You gave an answer too recently") {
+        Response::TooRecent(parse_wait(body).unwrap_or(Duration::from_secs(60)))
+    } else if body.contains("Did you already complete it") {
+        Response::AlreadySolved
+    } else {
+        Response::Unrecognised(body.chars().take(200).collect())
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Io(std::io::Error),
+    MissingSession,
+    /// Only parts 1 and 2 can be submitted.
+    InvalidPart(u8),
+    Http(String),
+    /// The registry already knows the outcome, so nothing was sent.
+    Known(Verdict),
+    /// The server asked us to wait and that time has not passed yet.
+    Cooldown(Duration),
+}
+
+impl std::fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitError::Io(e) => write!(f, "{e}"),
+            SubmitError::MissingSession => write!(f, "AOC_SESSION is not set"),
+            SubmitError::InvalidPart(p) => write!(f, "there is no part {p}"),
+            SubmitError::Http(e) => write!(f, "could not submit answer: {e}"),
+            SubmitError::Known(v) => write!(f, "answer is already known to be {v}"),
+            SubmitError::Cooldown(d) => write!(f, "must wait {}s before submitting", d.as_secs()),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+impl From<std::io::Error> for SubmitError {
+    fn from(e: std::io::Error) -> Self {
+        SubmitError::Io(e)
+    }
+}
+
+/// Posts answers to the server, keeping the answer registry and the wait the
+/// server imposes between submissions up to date in `answers/`.
+pub struct Submitter {
+    dir: PathBuf,
+    endpoint: String,
+    session: Option<String>,
+}
+
+impl Submitter {
+    pub fn new(dir: impl Into<PathBuf>, endpoint: &str, session: Option<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            endpoint: endpoint.trim_end_matches('/').to_owned(),
+            session,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new("answers", &endpoint_from_env(), session_from_env())
+    }
+
+    pub fn url(&self, year: u16, day: u8) -> String {
+        format!("{}/{year}/day/{day}/answer", self.endpoint)
+    }
+
+    fn cooldown_path(&self) -> PathBuf {
+        self.dir.join("cooldown")
+    }
+
+    /// How long until the server will accept another answer.
+    pub fn cooldown(&self) -> Option<Duration> {
+        let until: u64 = std::fs::read_to_string(self.cooldown_path())
+            .ok()?
+            .trim()
+            .parse()
+            .ok()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+        Duration::from_secs(until)
+            .checked_sub(now)
+            .filter(|d| !d.is_zero())
+    }
+
+    fn set_cooldown(&self, wait: Duration) -> std::io::Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let until = (now + wait).as_secs() + 1;
+
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.cooldown_path(), until.to_string())
+    }
+
+    pub fn submit(
+        &self,
+        year: u16,
+        day: u8,
+        part: u8,
+        answer: &str,
+    ) -> Result<Response, SubmitError> {
+        if !(1..=2).contains(&part) {
+            return Err(SubmitError::InvalidPart(part));
+        }
+
+        let mut registry = Registry::load(&self.dir, year, day)?;
+        if let Some(v) = registry.check(part, answer) {
+            return Err(SubmitError::Known(v));
+        }
+
+        if let Some(wait) = self.cooldown() {
+            return Err(SubmitError::Cooldown(wait));
+        }
+
+        let session = self.session.as_ref().ok_or(SubmitError::MissingSession)?;
+        let body = ureq::post(&self.url(year, day))
+            .set("Cookie", &format!("session={session}"))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|e| SubmitError::Http(e.to_string()))?
+            .into_string()?;
+
+        let response = parse_response(&body);
+        match &response {
+            Response::Checked(v, wait) => {
+                registry.record(part, answer, *v)?;
+                if let Some(wait) = wait {
+                    self.set_cooldown(*wait)?;
+                }
+            }
+            Response::TooRecent(wait) => self.set_cooldown(*wait)?,
+            Response::AlreadySolved | Response::Unrecognised(_) => {}
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::Server;
+
+    const TOO_LOW: &str = "That's not the right answer; your answer is too low.  If you're stuck, \
+        make sure you're using the full input data.  Please wait one minute before trying again.";
+    const RIGHT: &str = "That's the right answer!  You are one gold star closer to collecting \
+        enough star fruit.";
+
+    fn submitter(server: &Server) -> (tempfile::TempDir, Submitter) {
+        let dir = tempfile::tempdir().unwrap();
+        let submitter = Submitter::new(dir.path(), server.endpoint(), Some("abc".to_owned()));
+
+        (dir, submitter)
+    }
+
+    #[test]
+    fn waits() {
+        let wait = |body| parse_wait(body).map(|d: Duration| d.as_secs());
+
+        assert_eq!(wait("You have 4m 3s left to wait."), Some(243));
+        assert_eq!(wait("You have 1h 2m 3s left to wait."), Some(3723));
+        assert_eq!(wait("You have 30s left to wait."), Some(30));
+        assert_eq!(
+            wait("Please wait one minute before trying again."),
+            Some(60)
+        );
+        assert_eq!(
+            wait("Please wait 5 minutes before trying again."),
+            Some(300)
+        );
+        assert_eq!(wait("please wait 10 minutes"), Some(600));
+        assert_eq!(wait("You have 4x left to wait."), None);
+        assert_eq!(wait("Please wait a while."), None);
+        assert_eq!(wait("That's the right answer!"), None);
+    }
+
+    #[test]
+    fn responses() {
+        let minute = Some(Duration::from_secs(60));
+
+        assert_eq!(
+            parse_response(RIGHT),
+            Response::Checked(Verdict::Right, None)
+        );
+        assert_eq!(
+            parse_response(TOO_LOW),
+            Response::Checked(Verdict::TooLow, minute)
+        );
+        assert_eq!(
+            parse_response(
+                "That's not the right answer; your answer is too high.  \
+                 Please wait one minute before trying again."
+            ),
+            Response::Checked(Verdict::TooHigh, minute)
+        );
+        assert_eq!(
+            parse_response(
+                "That's not the right answer.  Please wait 5 minutes before trying again."
+            ),
+            Response::Checked(Verdict::Wrong, Some(Duration::from_secs(300)))
+        );
+        assert_eq!(
+            parse_response(
+                "You gave an answer too recently; you have to wait after submitting an \
+                 answer before trying again.  You have 4m 3s left to wait."
+            ),
+            Response::TooRecent(Duration::from_secs(243))
+        );
+        assert_eq!(
+            parse_response("You gave an answer too recently."),
+            Response::TooRecent(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_response(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Response::AlreadySolved
+        );
+        assert_eq!(
+            parse_response("500 Internal Server Error"),
+            Response::Unrecognised("500 Internal Server Error".to_owned())
+        );
+    }
+
+    #[test]
+    fn cooldown() {
+        let dir = tempfile::tempdir().unwrap();
+        let submitter = Submitter::new(dir.path(), "http://127.0.0.1:1", None);
+        assert_eq!(submitter.cooldown(), None);
+
+        submitter.set_cooldown(Duration::from_secs(60)).unwrap();
+        let wait = submitter.cooldown().unwrap();
+        assert!(wait > Duration::from_secs(59) && wait <= Duration::from_secs(61));
+
+        std::fs::write(dir.path().join("cooldown"), "1000").unwrap();
+        assert_eq!(submitter.cooldown(), None);
+    }
+
+    #[test]
+    fn records_a_wrong_answer_and_waits() {
+        let server = Server::start(200, TOO_LOW);
+        let (dir, submitter) = submitter(&server);
+
+        assert_eq!(
+            submitter.submit(2022, 25, 1, "42").unwrap(),
+            Response::Checked(Verdict::TooLow, Some(Duration::from_secs(60)))
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("POST /2022/day/25/answer "));
+        assert!(requests[0].contains("session=abc"));
+        assert!(requests[0].ends_with("level=1&answer=42"));
+
+        let registry = Registry::load(dir.path(), 2022, 25).unwrap();
+        assert_eq!(registry.check(1, "42"), Some(Verdict::TooLow));
+        assert!(submitter.cooldown().is_some());
+
+        assert!(matches!(
+            submitter.submit(2022, 25, 1, "40"),
+            Err(SubmitError::Known(Verdict::TooLow))
+        ));
+        assert!(matches!(
+            submitter.submit(2022, 25, 1, "50"),
+            Err(SubmitError::Cooldown(_))
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn records_the_right_answer() {
+        let server = Server::start(200, RIGHT);
+        let (dir, submitter) = submitter(&server);
+
+        assert_eq!(
+            submitter.submit(2022, 25, 2, "2=-1=0").unwrap(),
+            Response::Checked(Verdict::Right, None)
+        );
+        assert!(server.requests()[0].ends_with("level=2&answer=2%3D-1%3D0"));
+        assert_eq!(submitter.cooldown(), None);
+
+        let registry = Registry::load(dir.path(), 2022, 25).unwrap();
+        assert_eq!(registry.right_answer(2), Some("2=-1=0"));
+
+        assert!(matches!(
+            submitter.submit(2022, 25, 2, "2=-1=0"),
+            Err(SubmitError::Known(Verdict::Right))
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn sends_nothing_it_cannot_submit() {
+        let server = Server::start(200, RIGHT);
+        let (dir, submitter) = submitter(&server);
+
+        assert!(matches!(
+            submitter.submit(2022, 25, 3, "42"),
+            Err(SubmitError::InvalidPart(3))
+        ));
+
+        let submitter = Submitter::new(dir.path(), server.endpoint(), None);
+        assert!(matches!(
+            submitter.submit(2022, 25, 1, "42"),
+            Err(SubmitError::MissingSession)
+        ));
+
+        assert!(server.requests().is_empty());
+    }
+}
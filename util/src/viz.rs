@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use owo_colors::OwoColorize;
+
+use crate::args;
+
+pub type Rgb = [u8; 3];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// Sets a cell, ignoring positions outside the grid.
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = value;
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// A copy of the grid grown to `width` by `height`, keeping the contents
+    /// in the top left corner.
+    fn padded(&self, width: usize, height: usize, fill: T) -> Self {
+        let mut grid = Grid::new(width, height, fill);
+        for (y, row) in self.rows().enumerate() {
+            for (x, v) in row.iter().enumerate() {
+                grid.set(x, y, v.clone());
+            }
+        }
+
+        grid
+    }
+}
+
+impl Grid<char> {
+    /// Builds a grid from text, padding short lines with spaces.
+    pub fn from_lines(s: &str) -> Self {
+        let width = s.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let height = s.lines().count();
+
+        let mut grid = Grid::new(width, height, ' ');
+        for (y, l) in s.lines().enumerate() {
+            for (x, c) in l.chars().enumerate() {
+                grid.set(x, y, c);
+            }
+        }
+
+        grid
+    }
+}
+
+impl std::fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The colour a character is drawn with when turned into pixels.
+pub fn colour(c: char) -> Rgb {
+    match c {
+        ' ' | '.' => [16, 16, 24],
+        '#' => [200, 200, 200],
+        'o' | 'O' => [230, 190, 90],
+        '+' => [230, 80, 80],
+        'E' | 'H' => [90, 220, 110],
+        'T' => [240, 140, 40],
+        '~' | '|' => [80, 140, 240],
+        c => {
+            let h = (c as u32).wrapping_mul(2_654_435_761);
+            [
+                96 + (h >> 24) as u8 % 160,
+                96 + (h >> 16) as u8 % 160,
+                96 + (h >> 8) as u8 % 160,
+            ]
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    Text(Grid<char>),
+    Pixels(Grid<Rgb>),
+}
+
+impl Frame {
+    /// The frame as pixels, drawing each character or pixel as a `scale`
+    /// sized square.
+    pub fn to_pixels(&self, scale: usize) -> Grid<Rgb> {
+        let (width, height) = match self {
+            Frame::Text(g) => (g.width(), g.height()),
+            Frame::Pixels(g) => (g.width(), g.height()),
+        };
+
+        let mut pixels = Grid::new(width * scale, height * scale, [0; 3]);
+        for y in 0..height {
+            for x in 0..width {
+                let c = match self {
+                    Frame::Text(g) => colour(*g.get(x, y).unwrap()),
+                    Frame::Pixels(g) => *g.get(x, y).unwrap(),
+                };
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        pixels.set(x * scale + dx, y * scale + dy, c);
+                    }
+                }
+            }
+        }
+
+        pixels
+    }
+}
+
+/// Something that can be drawn as a sequence of frames.
+pub trait Visualize {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame> + '_>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    Terminal,
+    Gif(PathBuf),
+    Apng(PathBuf),
+    /// One `NNNN.png` file per frame in a directory.
+    Pngs(PathBuf),
+}
+
+impl Output {
+    fn from_path(path: &str) -> Self {
+        let path = PathBuf::from(path);
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gif") => Output::Gif(path),
+            Some("png" | "apng") => Output::Apng(path),
+            _ => Output::Pngs(path),
+        }
+    }
+}
+
+/// Where and how fast to play a visualization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub output: Output,
+    /// Frames per second, with 0 meaning step through the frames with enter
+    /// in the terminal.
+    pub fps: u32,
+    /// Side of the square of pixels each character or pixel is drawn as.
+    pub scale: usize,
+    /// Only every `step`th frame is shown, along with the last one.
+    pub step: usize,
+    /// Only the last frame is shown, as a still image.
+    pub last: bool,
+}
+
+impl Options {
+    /// Reads `--viz [path]`, `--fps N`, `--scale N`, `--step N` and `--last`
+    /// from the command line. Without a path the frames are played in the
+    /// terminal, otherwise the extension picks a GIF, an APNG or a directory
+    /// of numbered PNGs.
+    pub fn from_args(args: &[String]) -> Result<Option<Self>, String> {
+        let Some(path) = args::optional(args, "--viz") else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            output: path.map_or(Output::Terminal, Output::from_path),
+            fps: args::parse(args, "--fps")?.unwrap_or(10),
+            scale: args::parse(args, "--scale")?.map_or(4, |s: usize| s.max(1)),
+            step: args::parse(args, "--step")?.map_or(1, |s: usize| s.max(1)),
+            last: args::flag(args, "--last"),
+        }))
+    }
+
+    /// The frames to show out of all of them, as picked by `step` and `last`.
+    fn select<'a>(
+        &self,
+        frames: impl Iterator<Item = Frame> + 'a,
+    ) -> Box<dyn Iterator<Item = Frame> + 'a> {
+        if self.last {
+            return Box::new(frames.last().into_iter());
+        }
+
+        let step = self.step;
+        let mut frames = frames.enumerate().peekable();
+        Box::new(std::iter::from_fn(move || loop {
+            let (i, frame) = frames.next()?;
+            if i % step == 0 || frames.peek().is_none() {
+                return Some(frame);
+            }
+        }))
+    }
+
+    pub fn render(&self, frames: impl Iterator<Item = Frame>) -> std::io::Result<()> {
+        let frames = self.select(frames);
+
+        match &self.output {
+            Output::Terminal => play(frames, self.fps),
+            Output::Gif(path) => write_gif(path, &self.pixel_frames(frames), self.fps),
+            Output::Apng(path) => write_apng(path, &self.pixel_frames(frames), self.fps),
+            Output::Pngs(dir) => write_pngs(dir, frames, self.scale),
+        }
+    }
+
+    /// Every frame as pixels, padded to the size of the largest one since
+    /// animations need frames of a single size.
+    fn pixel_frames(&self, frames: impl Iterator<Item = Frame>) -> Vec<Grid<Rgb>> {
+        let frames: Vec<_> = frames.map(|f| f.to_pixels(self.scale)).collect();
+        let width = frames.iter().map(Grid::width).max().unwrap_or(0);
+        let height = frames.iter().map(Grid::height).max().unwrap_or(0);
+
+        frames
+            .iter()
+            .map(|f| f.padded(width, height, colour(' ')))
+            .collect()
+    }
+}
+
+fn print_frame(out: &mut impl Write, frame: &Frame) -> std::io::Result<()> {
+    match frame {
+        Frame::Text(g) => write!(out, "{g}"),
+        Frame::Pixels(g) => {
+            // Two rows of pixels per line, the top one as the foreground
+            // colour of a half block and the bottom one as its background.
+            for y in (0..g.height()).step_by(2) {
+                for x in 0..g.width() {
+                    let [r, gr, b] = *g.get(x, y).unwrap();
+                    let [r2, g2, b2] = *g.get(x, y + 1).unwrap_or(&[0; 3]);
+                    write!(out, "{}", '▀'.truecolor(r, gr, b).on_truecolor(r2, g2, b2))?;
+                }
+                writeln!(out)?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Plays the frames in the terminal at `fps` frames per second, or one frame
+/// per press of enter when `fps` is 0.
+pub fn play(frames: impl Iterator<Item = Frame>, fps: u32) -> std::io::Result<()> {
+    let stdout = std::io::stdout();
+    let mut line = String::new();
+
+    for (i, frame) in frames.enumerate() {
+        let mut out = stdout.lock();
+        write!(out, "\x1b[2J\x1b[H")?;
+        print_frame(&mut out, &frame)?;
+        writeln!(out, "Frame {}", i.bright_yellow())?;
+        out.flush()?;
+        drop(out);
+
+        if fps == 0 {
+            line.clear();
+            std::io::stdin().read_line(&mut line)?;
+        } else {
+            std::thread::sleep(Duration::from_secs_f64(1.0 / fps as f64));
+        }
+    }
+
+    Ok(())
+}
+
+fn write_gif(path: &Path, frames: &[Grid<Rgb>], fps: u32) -> std::io::Result<()> {
+    let Some(first) = frames.first() else {
+        return Ok(());
+    };
+
+    let mut palette: HashMap<Rgb, u8> = HashMap::new();
+    for &c in frames.iter().flat_map(|f| &f.cells) {
+        if palette.len() > 256 {
+            break;
+        }
+        let next = palette.len() as u8;
+        palette.entry(c).or_insert(next);
+    }
+
+    let global: Vec<u8> = if palette.len() <= 256 {
+        let mut colours: Vec<_> = palette.iter().collect();
+        colours.sort_by_key(|(_, &i)| i);
+        colours.into_iter().flat_map(|(c, _)| *c).collect()
+    } else {
+        Vec::new()
+    };
+
+    let (width, height) = (first.width() as u16, first.height() as u16);
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder =
+        gif::Encoder::new(file, width, height, &global).map_err(std::io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(std::io::Error::other)?;
+
+    let delay = (100 / fps.max(1)).max(1) as u16;
+    for f in frames {
+        let mut frame = if global.is_empty() {
+            let rgb: Vec<u8> = f.cells.iter().flatten().copied().collect();
+            gif::Frame::from_rgb_speed(width, height, &rgb, 10)
+        } else {
+            let indices: Vec<u8> = f.cells.iter().map(|c| palette[c]).collect();
+            gif::Frame::from_indexed_pixels(width, height, &indices, None)
+        };
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(std::io::Error::other)?;
+    }
+
+    Ok(())
+}
+
+fn png_encoder(file: File, grid: &Grid<Rgb>) -> png::Encoder<'static, BufWriter<File>> {
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(file),
+        grid.width() as u32,
+        grid.height() as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    encoder
+}
+
+fn write_apng(path: &Path, frames: &[Grid<Rgb>], fps: u32) -> std::io::Result<()> {
+    let Some(first) = frames.first() else {
+        return Ok(());
+    };
+
+    let mut encoder = png_encoder(File::create(path)?, first);
+    // A single frame is written as a plain PNG.
+    if frames.len() > 1 {
+        encoder
+            .set_animated(frames.len() as u32, 0)
+            .and_then(|_| encoder.set_frame_delay(1, fps.max(1) as u16))
+            .map_err(std::io::Error::other)?;
+    }
+
+    let mut writer = encoder.write_header().map_err(std::io::Error::other)?;
+    for f in frames {
+        let data: Vec<u8> = f.cells.iter().flatten().copied().collect();
+        writer
+            .write_image_data(&data)
+            .map_err(std::io::Error::other)?;
+    }
+
+    writer.finish().map_err(std::io::Error::other)
+}
+
+fn write_pngs(
+    dir: &Path,
+    frames: impl Iterator<Item = Frame>,
+    scale: usize,
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    for (i, frame) in frames.enumerate() {
+        write_png(&dir.join(format!("{i:04}.png")), &frame, scale)?;
+    }
+
+    Ok(())
+}
+
+/// Writes a single frame as a PNG image.
+pub fn write_png(path: &Path, frame: &Frame, scale: usize) -> std::io::Result<()> {
+    let grid = frame.to_pixels(scale);
+
+    let mut writer = png_encoder(File::create(path)?, &grid)
+        .write_header()
+        .map_err(std::io::Error::other)?;
+    let data: Vec<u8> = grid.cells.iter().flatten().copied().collect();
+
+    writer
+        .write_image_data(&data)
+        .map_err(std::io::Error::other)
+}
+
+/// Writes a single frame as a binary PPM image.
+pub fn write_ppm(path: &Path, frame: &Frame, scale: usize) -> std::io::Result<()> {
+    let grid = frame.to_pixels(scale);
+    let mut file = BufWriter::new(File::create(path)?);
+
+    write!(file, "P6\n{} {}\n255\n", grid.width(), grid.height())?;
+    for pixel in &grid.cells {
+        file.write_all(pixel)?;
+    }
+
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_owned).collect()
+    }
+
+    /// Frames showing their own index as a single digit.
+    fn numbered(n: usize) -> impl Iterator<Item = Frame> {
+        (0..n).map(|i| Frame::Text(Grid::from_lines(&i.to_string())))
+    }
+
+    fn read_png(path: &Path) -> (png::Info<'static>, Vec<u8>) {
+        let mut reader = png::Decoder::new(File::open(path).unwrap())
+            .read_info()
+            .unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut data).unwrap();
+
+        (reader.info().clone(), data)
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(Options::from_args(&args("day --last")), Ok(None));
+        assert_eq!(
+            Options::from_args(&args("day --viz")),
+            Ok(Some(Options {
+                output: Output::Terminal,
+                fps: 10,
+                scale: 4,
+                step: 1,
+                last: false,
+            }))
+        );
+        assert_eq!(
+            Options::from_args(&args("day --viz out.gif --fps 0 --scale 0 --step 3 --last")),
+            Ok(Some(Options {
+                output: Output::Gif("out.gif".into()),
+                fps: 0,
+                scale: 1,
+                step: 3,
+                last: true,
+            }))
+        );
+
+        let output = |a: &str| Options::from_args(&args(a)).unwrap().unwrap().output;
+        assert_eq!(output("day --viz out.png"), Output::Apng("out.png".into()));
+        assert_eq!(
+            output("day --viz out.apng"),
+            Output::Apng("out.apng".into())
+        );
+        assert_eq!(output("day --viz frames"), Output::Pngs("frames".into()));
+
+        assert_eq!(
+            Options::from_args(&args("day --viz --fps fast")),
+            Err("invalid --fps `fast`".to_owned())
+        );
+    }
+
+    #[test]
+    fn selects_frames() {
+        let mut options = Options::from_args(&args("day --viz --step 3"))
+            .unwrap()
+            .unwrap();
+        let shown = |options: &Options, n| -> Vec<Frame> { options.select(numbered(n)).collect() };
+
+        let expected: Vec<Frame> = [0, 3, 6, 7].map(|i| numbered(8).nth(i).unwrap()).to_vec();
+        assert_eq!(shown(&options, 8), expected);
+        assert_eq!(
+            shown(&options, 7),
+            numbered(7).step_by(3).collect::<Vec<_>>()
+        );
+
+        options.last = true;
+        assert_eq!(shown(&options, 8), numbered(8).skip(7).collect::<Vec<_>>());
+        assert!(shown(&options, 0).is_empty());
+    }
+
+    #[test]
+    fn writes_gifs_with_a_palette() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.gif");
+        let frames = [Grid::new(2, 1, [1, 2, 3]), Grid::new(2, 1, [4, 5, 6])];
+        write_gif(&path, &frames, 10).unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+        assert_eq!(decoder.global_palette(), Some(&[1, 2, 3, 4, 5, 6][..]));
+
+        for pixel in [[1, 2, 3, 255], [4, 5, 6, 255]] {
+            let frame = decoder.read_next_frame().unwrap().unwrap();
+            assert_eq!(frame.delay, 10);
+            assert!(frame.palette.is_none());
+            assert_eq!(*frame.buffer, pixel.repeat(2));
+        }
+        assert!(decoder.read_next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn writes_gifs_with_too_many_colours() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.gif");
+        let mut frame = Grid::new(257, 1, [0; 3]);
+        for x in 0..257 {
+            frame.set(x, 0, [x as u8, (x / 256) as u8, 0]);
+        }
+        write_gif(&path, &[frame], 10).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(File::open(&path).unwrap())
+            .unwrap();
+
+        // The frame is quantized to a palette of its own.
+        let frame = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!((frame.width, frame.height), (257, 1));
+        assert!(frame.palette.is_some());
+        assert!(decoder.read_next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn writes_apngs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.png");
+        let frames = [Grid::new(3, 2, [1, 2, 3]), Grid::new(3, 2, [4, 5, 6])];
+        write_apng(&path, &frames, 5).unwrap();
+
+        let (info, data) = read_png(&path);
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(info.animation_control.map(|a| a.num_frames), Some(2));
+        assert_eq!(data, [1, 2, 3].repeat(6));
+
+        write_apng(&path, &frames[1..], 5).unwrap();
+        let (info, data) = read_png(&path);
+        assert!(info.animation_control.is_none());
+        assert_eq!(data, [4, 5, 6].repeat(6));
+    }
+
+    #[test]
+    fn writes_pngs() {
+        let dir = tempfile::tempdir().unwrap();
+        let frames = dir.path().join("frames");
+        write_pngs(&frames, numbered(3), 2).unwrap();
+
+        let mut names: Vec<_> = std::fs::read_dir(&frames)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["0000.png", "0001.png", "0002.png"]);
+
+        let (info, data) = read_png(&frames.join("0001.png"));
+        assert_eq!((info.width, info.height), (2, 2));
+        assert_eq!(data, colour('1').repeat(4));
+    }
+
+    #[test]
+    fn writes_ppms() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.ppm");
+        write_ppm(&path, &Frame::Text(Grid::from_lines("#.")), 1).unwrap();
+
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend(colour('#'));
+        expected.extend(colour('.'));
+        assert_eq!(std::fs::read(&path).unwrap(), expected);
+    }
+}